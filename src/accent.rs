@@ -0,0 +1,583 @@
+use crate::{byte_offset, Morpheme, Parser, VerbType, WordClass};
+
+/// Pitch of a single mora
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pitch {
+    High,
+    Low,
+}
+
+/// Pitch accent pattern of a word, named after the position of its accent nucleus, the mora
+/// after which the pitch drops
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AccentPattern {
+    /// 平板, no drop. A following particle stays high
+    Heiban,
+    /// 頭高, drop after the first mora
+    Atamadaka,
+    /// 中高, drop after a mora within the word
+    Nakadaka,
+    /// 尾高, drop after the last mora. A following particle is low
+    Odaka,
+}
+
+impl AccentPattern {
+    /// Returns the pattern of a word with `morae` morae and its accent nucleus at `nucleus`,
+    /// where 0 means no nucleus
+    pub fn new(morae: usize, nucleus: usize) -> Self {
+        match nucleus {
+            0 => Self::Heiban,
+            1 => Self::Atamadaka,
+            n if n < morae => Self::Nakadaka,
+            _ => Self::Odaka,
+        }
+    }
+
+    /// Returns the pitch of a particle following the word
+    pub fn following_pitch(&self) -> Pitch {
+        match self {
+            Self::Heiban => Pitch::High,
+            _ => Pitch::Low,
+        }
+    }
+}
+
+/// Returns the pitch of each mora of a word with `morae` morae and its accent nucleus at
+/// `nucleus`, where 0 means no nucleus. The first mora is low unless it carries the nucleus, the
+/// following ones stay high up to the nucleus. A nucleus beyond the last mora is treated as odaka
+pub fn pitch_pattern(morae: usize, nucleus: usize) -> Vec<Pitch> {
+    (1..=morae)
+        .map(|mora| match (mora, nucleus) {
+            (1, 1) => Pitch::High,
+            (1, _) => Pitch::Low,
+            (_, 0) => Pitch::High,
+            (mora, nucleus) if mora <= nucleus => Pitch::High,
+            _ => Pitch::Low,
+        })
+        .collect()
+}
+
+/// Splits a kana string into its morae. Small kana like the ョ of ショ belong to the mora before
+/// them, while ッ, ン and ー are morae of their own
+pub fn morae(kana: &str) -> Vec<&str> {
+    let mut morae: Vec<&str> = Vec::new();
+    let mut start = 0;
+
+    for (i, c) in kana.char_indices() {
+        if i > start && !is_glide(c) {
+            morae.push(&kana[start..i]);
+            start = i;
+        }
+    }
+
+    if start < kana.len() {
+        morae.push(&kana[start..]);
+    }
+    morae
+}
+
+/// Returns `true` for small kana which don't form a mora of their own
+fn is_glide(c: char) -> bool {
+    matches!(
+        c,
+        'ァ' | 'ィ'
+            | 'ゥ'
+            | 'ェ'
+            | 'ォ'
+            | 'ャ'
+            | 'ュ'
+            | 'ョ'
+            | 'ヮ'
+            | 'ぁ'
+            | 'ぃ'
+            | 'ぅ'
+            | 'ぇ'
+            | 'ぉ'
+            | 'ゃ'
+            | 'ゅ'
+            | 'ょ'
+            | 'ゎ'
+    )
+}
+
+/// A rule of the accent combination type (aConType), telling how the accent of a morpheme
+/// combines with the morphemes around it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AccentRule<'a> {
+    /// C1–C5, rules of a noun or suffix combining with a preceding noun into a compound
+    Compound(u8),
+    /// P1–P6, rules of a prefix combining with the following word
+    Prefix(u8),
+    /// F1–F6, rules of a particle or auxilary attaching to the preceding word, with the optional
+    /// mora offset given after `@`
+    Following(u8, Option<i32>),
+    Unknown(&'a str),
+}
+
+impl<'a> AccentRule<'a> {
+    /// Parses a rule like `C1` or `F2@-1`, keeping unmapped values as `Unknown`
+    pub fn from_str_lenient(raw: &'a str) -> Self {
+        let (rule, offset) = match raw.split_once('@') {
+            Some((rule, offset)) => match offset.parse() {
+                Ok(offset) => (rule, Some(offset)),
+                Err(_) => return Self::Unknown(raw),
+            },
+            None => (raw, None),
+        };
+
+        let kind = rule.get(..1).unwrap_or("");
+        let number: u8 = match rule.get(1..).and_then(|number| number.parse().ok()) {
+            Some(number) => number,
+            None => return Self::Unknown(raw),
+        };
+
+        match (kind, offset) {
+            ("C", None) => Self::Compound(number),
+            ("P", None) => Self::Prefix(number),
+            ("F", offset) => Self::Following(number, offset),
+            _ => Self::Unknown(raw),
+        }
+    }
+}
+
+/// A single entry of the accent combination type (aConType)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccentConnection<'a> {
+    /// The part of speech of the preceding word the rule applies to, like `動詞` for
+    /// `動詞%F2@0`. `None` if the rule applies to all of them
+    pub word_class: Option<&'a str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub rule: AccentRule<'a>,
+}
+
+impl<'a> AccentConnection<'a> {
+    /// Parses all entries of a raw aConType like `動詞%F2@0,形容詞%F1`. Returns an empty list
+    /// for `*`
+    pub fn parse_all(raw: &'a str) -> Vec<Self> {
+        raw.split(',')
+            .filter(|entry| !entry.is_empty() && *entry != "*")
+            .map(|entry| {
+                let (word_class, rule) = match entry.split_once('%') {
+                    Some((word_class, rule)) => (Some(word_class), rule),
+                    None => (None, entry),
+                };
+                AccentConnection {
+                    word_class,
+                    rule: AccentRule::from_str_lenient(rule),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The accent modification type (aModType) like `M4@1`, telling how a morpheme changes the
+/// accent of the word it modifies
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccentModification {
+    /// The number of the M rule
+    pub kind: u8,
+    /// The mora offset given after `@`
+    pub offset: i32,
+}
+
+impl AccentModification {
+    /// Parses a raw aModType. Returns `None` for `*` and values which can't be mapped
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (kind, offset) = raw.strip_prefix('M')?.split_once('@')?;
+        Some(AccentModification {
+            kind: kind.parse().ok()?,
+            offset: offset.parse().ok()?,
+        })
+    }
+}
+
+impl<'dict, 'input> Morpheme<'dict, 'input> {
+    /// Returns the morae of the pronunciation of the morpheme
+    pub fn morae(&self) -> Vec<&'dict str> {
+        match self.reading {
+            "*" => Vec::new(),
+            reading => morae(reading),
+        }
+    }
+
+    /// Returns all accent nuclei of the morpheme listed in aType, the most common one first.
+    /// 0 means the morpheme has no nucleus
+    pub fn accent_nuclei(&self) -> Vec<usize> {
+        self.features()
+            .a_type
+            .split(',')
+            .filter_map(|nucleus| nucleus.trim().parse().ok())
+            .collect()
+    }
+
+    /// Returns the most common accent nucleus of the morpheme. 0 means the morpheme has no
+    /// nucleus, `None` that UniDic has no accent information for it
+    pub fn accent_nucleus(&self) -> Option<usize> {
+        self.accent_nuclei().first().copied()
+    }
+
+    /// Returns the accent pattern of the morpheme, based on its most common accent nucleus
+    pub fn accent_pattern(&self) -> Option<AccentPattern> {
+        let nucleus = self.accent_nucleus()?;
+        Some(AccentPattern::new(self.morae().len(), nucleus))
+    }
+
+    /// Returns the pitch of each mora of the morpheme spoken on its own, based on its most
+    /// common accent nucleus
+    pub fn pitch(&self) -> Option<Vec<Pitch>> {
+        let nucleus = self.accent_nucleus()?;
+        Some(pitch_pattern(self.morae().len(), nucleus))
+    }
+
+    /// Returns the accent combination rules (aConType) of the morpheme
+    pub fn accent_connections(&self) -> Vec<AccentConnection<'dict>> {
+        AccentConnection::parse_all(self.features().a_con_type)
+    }
+
+    /// Returns the accent modification type (aModType) of the morpheme
+    pub fn accent_modification(&self) -> Option<AccentModification> {
+        AccentModification::parse(self.features().a_mod_type)
+    }
+}
+
+/// A content word together with the particles, auxilaries and suffixes attached to it, spoken
+/// with a single pitch accent
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccentPhrase<'dict, 'input> {
+    /// The phrase as it appears in the input
+    pub surface: &'input str,
+    /// Byte offset of the phrase within the input
+    pub start: usize,
+    /// Accent nucleus of the whole phrase, 0 if it has none
+    pub nucleus: usize,
+    /// The morphemes of the phrase. Never empty
+    pub morphemes: Vec<Morpheme<'dict, 'input>>,
+}
+
+impl<'dict, 'input> AccentPhrase<'dict, 'input> {
+    /// Returns the morae of the phrase
+    pub fn morae(&self) -> Vec<&'dict str> {
+        self.morphemes
+            .iter()
+            .flat_map(|morph| morph.morae())
+            .collect()
+    }
+
+    /// Returns the accent pattern of the phrase
+    pub fn pattern(&self) -> AccentPattern {
+        AccentPattern::new(self.morae().len(), self.nucleus)
+    }
+
+    /// Returns the pitch of each mora of the phrase
+    pub fn pitch(&self) -> Vec<Pitch> {
+        pitch_pattern(self.morae().len(), self.nucleus)
+    }
+
+    /// Returns each mora of the phrase along with its pitch
+    pub fn pitch_marks(&self) -> Vec<(&'dict str, Pitch)> {
+        let morae = self.morae();
+        let pitch = pitch_pattern(morae.len(), self.nucleus);
+        morae.into_iter().zip(pitch).collect()
+    }
+}
+
+impl Parser {
+    /// Parses `text` and splits it into accent phrases. See [`accent_phrases`]
+    pub fn accent_phrases<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+    ) -> Vec<AccentPhrase<'dict, 'text>> {
+        accent_phrases(text, self.parse_lenient(text))
+    }
+}
+
+/// Splits `morphemes`, which have to be parsed from `text`, into accent phrases and computes the
+/// accent of each phrase. Particles, auxilaries, suffixes, helper verbs after て and nouns
+/// following a noun or prefix are attached to the phrase before them. Symbols and spaces end a
+/// phrase and are not part of any.
+///
+/// The accent of a phrase starts as the one of its first morpheme. Each attached morpheme then
+/// changes it by the aConType rule matching the preceding morpheme, where `M` is the amount of
+/// morae before the attached morpheme, `A` the accent of the attached morpheme and `n` the
+/// offset after `@`:
+/// - C1: `M + A`, or heiban if `A` is heiban, C2: `M + 1`, C3: `M`, C4: heiban, C5: unchanged
+/// - F1: unchanged, F2: `M + n` if heiban, F3: `M + n` unless heiban, F4: `M + n`, F5: heiban
+/// - P1: heiban if `A` is heiban, else `M + A`. P2: `M + 1` if `A` is heiban, else `M + A`.
+///   P6: heiban. These apply to the morpheme following a prefix, using the rule of the prefix
+///
+/// The remaining rules and morphemes without a rule keep the accent unchanged, except after a
+/// prefix, where the attached morpheme keeps its own accent
+pub fn accent_phrases<'dict, 'input>(
+    text: &'input str,
+    morphemes: Vec<Morpheme<'dict, 'input>>,
+) -> Vec<AccentPhrase<'dict, 'input>> {
+    let mut phrases: Vec<AccentPhrase> = Vec::new();
+
+    for morph in morphemes {
+        // Skipping them ends the current phrase, as the next morpheme isn't contiguous to it
+        if matches!(morph.word_class, WordClass::Symbol | WordClass::Space) {
+            continue;
+        }
+
+        let start = byte_offset(text, morph.surface).unwrap_or(morph.start);
+
+        if let Some(phrase) = phrases.last_mut() {
+            let prev = phrase.morphemes.last();
+            let contiguous = phrase.start + phrase.surface.len() == start;
+            if let Some(prev) = prev.filter(|prev| contiguous && joins(prev, &morph)) {
+                let morae = phrase.morae().len() as i64;
+                let nucleus = combine(prev, &morph, morae, phrase.nucleus as i64);
+                phrase.nucleus = nucleus.clamp(0, morae + morph.morae().len() as i64) as usize;
+                phrase.surface = &text[phrase.start..start + morph.surface.len()];
+                phrase.morphemes.push(morph);
+                continue;
+            }
+        }
+
+        phrases.push(AccentPhrase {
+            surface: morph.surface,
+            start,
+            nucleus: morph.accent_nucleus().unwrap_or(0),
+            morphemes: vec![morph],
+        });
+    }
+
+    phrases
+}
+
+/// Returns `true` if `morph` belongs to the accent phrase ending with `prev`
+fn joins(prev: &Morpheme, morph: &Morpheme) -> bool {
+    let pos2 = morph.features().pos2;
+
+    match morph.word_class {
+        _ if prev.word_class == WordClass::Prefix => true,
+        WordClass::Particle(_) | WordClass::Suffix => true,
+        WordClass::Verb(VerbType::Auxilary(_)) => true,
+        // ている, てほしい
+        WordClass::Verb(_) | WordClass::Adjective(_) => {
+            pos2 == "非自立可能"
+                && matches!(prev.surface, "て" | "で")
+                && prev.word_class.is_particle()
+        }
+        // Compound nouns like 東京都
+        WordClass::Noun(_) => prev.word_class.is_noun(),
+        _ => false,
+    }
+}
+
+/// Returns the accent nucleus of a phrase with `morae` morae and the nucleus `nucleus` after
+/// attaching `morph` to its last morpheme `prev`
+fn combine(prev: &Morpheme, morph: &Morpheme, morae: i64, nucleus: i64) -> i64 {
+    let own = morph.accent_nucleus().unwrap_or(0) as i64;
+
+    if prev.word_class == WordClass::Prefix {
+        let rule = connection_rule(prev, None);
+        return match rule {
+            Some(AccentRule::Prefix(1)) if own == 0 => 0,
+            Some(AccentRule::Prefix(2)) if own == 0 => morae + 1,
+            Some(AccentRule::Prefix(6)) => 0,
+            _ if own == 0 => 0,
+            _ => morae + own,
+        };
+    }
+
+    match connection_rule(morph, Some(prev)) {
+        Some(AccentRule::Compound(1)) if own == 0 => 0,
+        Some(AccentRule::Compound(1)) => morae + own,
+        Some(AccentRule::Compound(2)) => morae + 1,
+        Some(AccentRule::Compound(3)) => morae,
+        Some(AccentRule::Compound(4)) => 0,
+        Some(AccentRule::Following(2, offset)) if nucleus == 0 => {
+            morae + offset.unwrap_or(0) as i64
+        }
+        Some(AccentRule::Following(3, offset)) if nucleus != 0 => {
+            morae + offset.unwrap_or(0) as i64
+        }
+        Some(AccentRule::Following(4, offset)) => morae + offset.unwrap_or(0) as i64,
+        Some(AccentRule::Following(5, _)) => 0,
+        _ => nucleus,
+    }
+}
+
+/// Returns the aConType rule of `morph` which applies after `prev`. Rules restricted to the part
+/// of speech of `prev` take precedence over unrestricted ones
+fn connection_rule<'dict>(
+    morph: &Morpheme<'dict, '_>,
+    prev: Option<&Morpheme>,
+) -> Option<AccentRule<'dict>> {
+    let connections = morph.accent_connections();
+    let prev_pos = prev.map(|prev| prev.features().pos1);
+
+    connections
+        .iter()
+        .find(|connection| connection.word_class.is_some() && connection.word_class == prev_pos)
+        .or_else(|| {
+            connections
+                .iter()
+                .find(|connection| connection.word_class.is_none())
+        })
+        .map(|connection| connection.rule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::morph;
+
+    /// Returns the features of `surface` with the given accent columns
+    fn feature(pos: &str, surface: &str, pron: &str, accent: &str) -> String {
+        format!(
+            "{pos},*,*,*,{pron},{s},{s},{pron},{s},{pron},*,*,*,*,*,*,*,*,{pron},{pron},{pron},{pron},{accent},*,*",
+            pos = pos,
+            s = surface,
+            pron = pron,
+            accent = accent
+        )
+    }
+
+    #[test]
+    fn pitch_patterns() {
+        use Pitch::{High as H, Low as L};
+
+        assert_eq!(AccentPattern::new(3, 0), AccentPattern::Heiban);
+        assert_eq!(AccentPattern::new(3, 1), AccentPattern::Atamadaka);
+        assert_eq!(AccentPattern::new(3, 2), AccentPattern::Nakadaka);
+        assert_eq!(AccentPattern::new(3, 3), AccentPattern::Odaka);
+        assert_eq!(AccentPattern::Heiban.following_pitch(), H);
+        assert_eq!(AccentPattern::Odaka.following_pitch(), L);
+
+        assert_eq!(pitch_pattern(3, 0), vec![L, H, H]);
+        assert_eq!(pitch_pattern(3, 1), vec![H, L, L]);
+        assert_eq!(pitch_pattern(3, 2), vec![L, H, L]);
+        assert_eq!(pitch_pattern(3, 3), vec![L, H, H]);
+        assert_eq!(pitch_pattern(1, 1), vec![H]);
+    }
+
+    #[test]
+    fn morae_keep_small_kana_with_the_mora_before() {
+        assert_eq!(morae("トーキョー"), vec!["ト", "ー", "キョ", "ー"]);
+        assert_eq!(morae("ガッコー"), vec!["ガ", "ッ", "コ", "ー"]);
+        assert_eq!(morae("シンブン"), vec!["シ", "ン", "ブ", "ン"]);
+        assert!(morae("").is_empty());
+    }
+
+    #[test]
+    fn accent_columns() {
+        assert_eq!(AccentRule::from_str_lenient("C3"), AccentRule::Compound(3));
+        assert_eq!(AccentRule::from_str_lenient("P2"), AccentRule::Prefix(2));
+        assert_eq!(
+            AccentRule::from_str_lenient("F2@-1"),
+            AccentRule::Following(2, Some(-1))
+        );
+        assert_eq!(
+            AccentRule::from_str_lenient("X1"),
+            AccentRule::Unknown("X1")
+        );
+
+        assert_eq!(
+            AccentConnection::parse_all("動詞%F2@0,名詞%F1"),
+            vec![
+                AccentConnection {
+                    word_class: Some("動詞"),
+                    rule: AccentRule::Following(2, Some(0)),
+                },
+                AccentConnection {
+                    word_class: Some("名詞"),
+                    rule: AccentRule::Following(1, None),
+                },
+            ]
+        );
+        assert!(AccentConnection::parse_all("*").is_empty());
+
+        assert_eq!(
+            AccentModification::parse("M4@1"),
+            Some(AccentModification { kind: 4, offset: 1 })
+        );
+        assert_eq!(AccentModification::parse("*"), None);
+    }
+
+    #[test]
+    fn morpheme_accent() {
+        let hashi = feature("名詞,普通名詞,一般", "箸", "ハシ", "\"1,2\",C3,*");
+        let hashi = morph("箸", 0, &hashi);
+        assert_eq!(hashi.accent_nuclei(), vec![1, 2]);
+        assert_eq!(hashi.accent_nucleus(), Some(1));
+        assert_eq!(hashi.accent_pattern(), Some(AccentPattern::Atamadaka));
+        assert_eq!(hashi.pitch(), Some(vec![Pitch::High, Pitch::Low]));
+
+        let unknown = feature("名詞,普通名詞,一般", "箸", "ハシ", "*,*,*");
+        let unknown = morph("箸", 0, &unknown);
+        assert_eq!(unknown.accent_nucleus(), None);
+        assert_eq!(unknown.pitch(), None);
+    }
+
+    #[test]
+    fn phrases_combine_accents() {
+        let text = "東京都に箸が";
+        let particle = "*,\"動詞%F2@0,名詞%F1\",*";
+        let words = [
+            (0, 6, "名詞,固有名詞,地名", "トーキョー", "0,C1,*"),
+            (6, 9, "接尾辞,名詞的,一般", "ト", "*,C3,*"),
+            (9, 12, "助詞,格助詞,*", "ニ", particle),
+            (12, 15, "名詞,普通名詞,一般", "ハシ", "1,C3,*"),
+            (15, 18, "助詞,格助詞,*", "ガ", particle),
+        ];
+        let features: Vec<String> = words
+            .iter()
+            .map(|&(start, end, pos, pron, accent)| feature(pos, &text[start..end], pron, accent))
+            .collect();
+        let morphemes = words
+            .iter()
+            .zip(&features)
+            .map(|(&(start, end, ..), feature)| morph(&text[start..end], start, feature))
+            .collect();
+
+        let phrases = accent_phrases(text, morphemes);
+        assert_eq!(phrases.len(), 2);
+
+        assert_eq!(phrases[0].surface, "東京都に");
+        assert_eq!(phrases[0].nucleus, 4);
+        assert_eq!(phrases[0].pattern(), AccentPattern::Nakadaka);
+
+        assert_eq!(phrases[1].surface, "箸が");
+        assert_eq!(phrases[1].start, 12);
+        assert_eq!(phrases[1].pattern(), AccentPattern::Atamadaka);
+        assert_eq!(
+            phrases[1].pitch_marks(),
+            vec![("ハ", Pitch::High), ("シ", Pitch::Low), ("ガ", Pitch::Low)]
+        );
+    }
+
+    #[test]
+    fn heiban_compounds_stay_heiban() {
+        let text = "東京大学";
+        let features = [
+            feature("名詞,固有名詞,地名", "東京", "トーキョー", "0,C1,*"),
+            feature("名詞,普通名詞,一般", "大学", "ダイガク", "0,C1,*"),
+        ];
+        let morphemes = vec![
+            morph(&text[..6], 0, &features[0]),
+            morph(&text[6..], 6, &features[1]),
+        ];
+        let phrases = accent_phrases(text, morphemes);
+        assert_eq!(phrases.len(), 1);
+        assert_eq!(phrases[0].nucleus, 0);
+        assert_eq!(phrases[0].pattern(), AccentPattern::Heiban);
+
+        // トーキョー has four morae, so the nucleus is on the first mora of ダイガク
+        let features = [
+            features[0].clone(),
+            feature("名詞,普通名詞,一般", "大学", "ダイガク", "1,C1,*"),
+        ];
+        let morphemes = vec![
+            morph(&text[..6], 0, &features[0]),
+            morph(&text[6..], 6, &features[1]),
+        ];
+        assert_eq!(accent_phrases(text, morphemes)[0].nucleus, 5);
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{Morpheme, Parser};
+
+/// Amount of texts a thread takes at once
+const BLOCK_SIZE: usize = 16;
+
+// `parse_batch` shares the parser, and with it igo's `Tagger`, between threads. Fail to compile
+// instead if an update of igo makes the tagger lose `Send` or `Sync`
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<igo::Tagger>();
+    assert_send_sync::<Parser>();
+};
+
+impl Parser {
+    /// Parses all `texts` on as many threads as there are cores. The dictionary is shared
+    /// between the threads. Morphemes are parsed leniently, see [`Parser::parse_lenient`]. The
+    /// result contains the morphemes of each text in the order of `texts`
+    pub fn parse_batch<'text, 'dict>(
+        &'dict self,
+        texts: &[&'text str],
+    ) -> Vec<Vec<Morpheme<'dict, 'text>>> {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        self.parse_batch_with_threads(texts, threads)
+    }
+
+    /// Same as [`Parser::parse_batch`] but uses at most `threads` threads
+    pub fn parse_batch_with_threads<'text, 'dict>(
+        &'dict self,
+        texts: &[&'text str],
+        threads: usize,
+    ) -> Vec<Vec<Morpheme<'dict, 'text>>> {
+        in_parallel(texts, threads, |text| self.parse_lenient(text))
+    }
+}
+
+/// Calls `f` with each of `items` on at most `threads` threads and returns the results in the
+/// order of `items`
+fn in_parallel<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let blocks = items.len().div_ceil(BLOCK_SIZE);
+    let threads = threads.min(blocks).max(1);
+    if threads == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    // Threads take blocks of items until none are left, so long texts don't hold up a single
+    // thread while the others are idle
+    let next_block = AtomicUsize::new(0);
+    let done: Vec<Vec<(usize, R)>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let start = next_block.fetch_add(1, Ordering::Relaxed) * BLOCK_SIZE;
+                        if start >= items.len() {
+                            return done;
+                        }
+
+                        let end = (start + BLOCK_SIZE).min(items.len());
+                        for (i, item) in items[start..end].iter().enumerate() {
+                            done.push((start + i, f(item)));
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .collect()
+    });
+
+    let mut results: Vec<Option<R>> = items.iter().map(|_| None).collect();
+    for (i, result) in done.into_iter().flatten() {
+        results[i] = Some(result);
+    }
+    results
+        .into_iter()
+        .map(|result| result.expect("every item is processed once"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Mutex, thread::ThreadId, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn results_keep_the_order_of_the_items() {
+        let items: Vec<usize> = (0..BLOCK_SIZE * 10 + 3).collect();
+        let threads = Mutex::new(HashSet::<ThreadId>::new());
+        let results = in_parallel(&items, 4, |&i| {
+            threads.lock().unwrap().insert(thread::current().id());
+            // Let the first blocks take longer, so later blocks finish first
+            if i < BLOCK_SIZE * 2 {
+                thread::sleep(Duration::from_millis(1));
+            }
+            i * 2
+        });
+
+        assert_eq!(results, items.iter().map(|i| i * 2).collect::<Vec<_>>());
+        assert!(threads.into_inner().unwrap().len() > 1);
+    }
+
+    #[test]
+    fn small_and_empty_batches() {
+        let empty: [&str; 0] = [];
+        assert!(in_parallel(&empty, 4, |text| text.len()).is_empty());
+        assert_eq!(in_parallel(&["a", "bc"], 4, |text| text.len()), vec![1, 2]);
+        assert_eq!(in_parallel(&["a", "bc"], 0, |text| text.len()), vec![1, 2]);
+    }
+}
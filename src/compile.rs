@@ -0,0 +1,923 @@
+//! Compiles UniDic's MeCab sources into the binary dictionary layout igo's `Tagger` loads.
+//!
+//! The layout is the one of igo's `BuildDic` tool. All numbers and all text, which is UTF-16, are
+//! written little endian whatever the platform, so a compiled dictionary can be copied between
+//! machines. igo reads them in the byte order of the machine, so only little endian machines can
+//! load them into igo:
+//!
+//! - `word2id`: a double array trie of all surfaces and unknown word categories, mapping them to
+//!   their key id
+//! - `word.ary.idx`: for each key id, the index of its first word, followed by the number of words
+//! - `word.inf`: for each word the start of its features in `word.dat`, then for each word its
+//!   left id, its right id and its cost, each followed by a sentinel
+//! - `word.dat`: the features of all words
+//! - `matrix.bin`: the connection costs of `matrix.def`
+//! - `char.category`: the categories of `char.def`
+//! - `code2category`: for each UTF-16 code unit the index of its category, then its category mask
+
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    trie::{self, CODE_LIMIT, TERMINATE_CODE},
+    CompileError,
+};
+
+/// Source file holding the words
+pub const LEX_FILE: &str = "lex.csv";
+/// Source file holding the connection costs
+pub const MATRIX_FILE: &str = "matrix.def";
+/// Source file holding the character categories
+pub const CHAR_FILE: &str = "char.def";
+/// Source file holding the words used for unknown text of each character category
+pub const UNK_FILE: &str = "unk.def";
+
+/// Number of lines after which [`CompileProgress::Reading`] is reported
+const PROGRESS_LINES: usize = 100_000;
+
+/// Progress of [`compile_dictionary`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompileProgress<'a> {
+    /// `lines` lines of the source file `file` have been read. Reported every 100000 lines and
+    /// once the file has been read completely
+    Reading { file: &'a Path, lines: usize },
+    /// The trie of all `keys` distinct surfaces and categories is being built
+    BuildingTrie { keys: usize },
+    /// The dictionary file `file` of `bytes` bytes has been written
+    Written { file: &'a Path, bytes: usize },
+}
+
+/// Sizes of a compiled dictionary, returned by [`compile_dictionary`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CompileSummary {
+    /// Words of `lex.csv` and `unk.def`
+    pub words: usize,
+    /// Distinct surfaces and categories
+    pub keys: usize,
+    /// Character categories of `char.def`
+    pub categories: usize,
+    /// Left context ids of `matrix.def`
+    pub left_ids: usize,
+    /// Right context ids of `matrix.def`
+    pub right_ids: usize,
+}
+
+/// Compiles the UniDic sources `lex.csv`, `matrix.def`, `char.def` and `unk.def` in `source` into
+/// a dictionary in `output`, which can then be loaded by [`Parser::new`](crate::Parser::new).
+/// `output` is created if it doesn't exist and existing dictionary files are replaced.
+///
+/// The sources are validated before anything is written: every line has to be complete, all
+/// context ids have to be defined by `matrix.def`, the matrix has to be complete and every
+/// category of `char.def` needs words in `unk.def`. The output only depends on the content of the
+/// sources, so compiling the same UniDic release always results in the same files
+pub fn compile_dictionary<S, O, F>(
+    source: S,
+    output: O,
+    mut progress: F,
+) -> Result<CompileSummary, CompileError>
+where
+    S: AsRef<Path>,
+    O: AsRef<Path>,
+    F: FnMut(CompileProgress),
+{
+    let (source, output) = (source.as_ref(), output.as_ref());
+
+    let matrix = Matrix::read(&source.join(MATRIX_FILE), &mut progress)?;
+    let categories = CharDef::read(&source.join(CHAR_FILE), &mut progress)?;
+
+    let mut words = Vec::new();
+    let unk_file = source.join(UNK_FILE);
+    read_words(
+        &unk_file,
+        &matrix,
+        Some(&categories),
+        &mut words,
+        &mut progress,
+    )?;
+    read_words(
+        &source.join(LEX_FILE),
+        &matrix,
+        None,
+        &mut words,
+        &mut progress,
+    )?;
+
+    let mut keys: Vec<Vec<u16>> = words.iter().map(|word| word.key.clone()).collect();
+    keys.sort_unstable();
+    keys.dedup();
+
+    progress(CompileProgress::BuildingTrie { keys: keys.len() });
+    let trie = trie::build(&keys);
+
+    let key_id = |key: &[u16]| keys.binary_search_by(|probe| probe[..].cmp(key)).ok();
+    let mut category_ids = Vec::with_capacity(categories.names.len());
+    for name in &categories.names {
+        let key: Vec<u16> = name.encode_utf16().collect();
+        let id = key_id(&key).ok_or_else(|| CompileError::MissingCategory(name.clone()))?;
+        category_ids.push(id);
+    }
+
+    // Group the words by their key, keeping the order of the sources within each key
+    let mut words: Vec<(usize, Word)> = words
+        .into_iter()
+        .map(|word| (key_id(&word.key).unwrap_or_default(), word))
+        .collect();
+    words.sort_by_key(|(id, _)| *id);
+
+    fs::create_dir_all(output).map_err(|source| CompileError::Io {
+        file: output.to_path_buf(),
+        source,
+    })?;
+
+    let mut writer = DictionaryWriter {
+        output,
+        progress: &mut progress,
+    };
+    writer.write("word2id", &trie)?;
+    writer.write("word.ary.idx", &word_indices(&words, keys.len()))?;
+    let (info, data) = word_info(&words);
+    writer.write("word.inf", &info)?;
+    writer.write("word.dat", &data)?;
+    writer.write("matrix.bin", &matrix.to_bytes())?;
+    writer.write("char.category", &categories.category_bytes(&category_ids))?;
+    writer.write("code2category", &categories.code_bytes())?;
+
+    Ok(CompileSummary {
+        words: words.len(),
+        keys: keys.len(),
+        categories: categories.names.len(),
+        left_ids: matrix.left_ids,
+        right_ids: matrix.right_ids,
+    })
+}
+
+/// A word of `lex.csv` or `unk.def`
+struct Word {
+    /// The surface as UTF-16
+    key: Vec<u16>,
+    left_id: i16,
+    right_id: i16,
+    cost: i16,
+    /// The feature columns as they are written in the source, including their quotes
+    feature: String,
+}
+
+/// Reads the words of `file`, which has the `lex.csv` layout
+/// `surface,left id,right id,cost,features...`, into `words`. The surfaces of `unk.def` have to
+/// be one of its `categories`
+fn read_words(
+    file: &Path,
+    matrix: &Matrix,
+    categories: Option<&CharDef>,
+    words: &mut Vec<Word>,
+    progress: &mut impl FnMut(CompileProgress),
+) -> Result<(), CompileError> {
+    read_lines(file, progress, |line_nr, line| {
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        let (columns, feature) = match split_columns(line, 4) {
+            Some((columns, feature)) if !feature.is_empty() => (columns, feature),
+            Some((columns, _)) => {
+                return Err(CompileError::Columns {
+                    file: file.to_path_buf(),
+                    line: line_nr,
+                    count: columns.len(),
+                })
+            }
+            None => {
+                return Err(CompileError::Columns {
+                    file: file.to_path_buf(),
+                    line: line_nr,
+                    count: line.split(',').count(),
+                })
+            }
+        };
+
+        let surface = columns[0].clone();
+        let key: Vec<u16> = surface.encode_utf16().collect();
+        if key.is_empty() || key.contains(&TERMINATE_CODE) || key.len() > i16::MAX as usize {
+            return Err(CompileError::Surface {
+                file: file.to_path_buf(),
+                line: line_nr,
+                value: surface,
+            });
+        }
+
+        if categories.is_some_and(|c| !c.names.contains(&surface)) {
+            return Err(CompileError::UnknownCategory {
+                file: file.to_path_buf(),
+                line: line_nr,
+                name: surface,
+            });
+        }
+
+        let number = |value: &str| parse_number(value, file, line_nr);
+        let context_id = |value: &str, limit: usize| {
+            let id = number(value)?;
+            check_context_id(id, limit, file, line_nr).map(|_| id)
+        };
+
+        words.push(Word {
+            left_id: context_id(&columns[1], matrix.left_ids)?,
+            right_id: context_id(&columns[2], matrix.right_ids)?,
+            cost: number(&columns[3])?,
+            key,
+            feature: feature.to_string(),
+        });
+        Ok(())
+    })
+}
+
+/// Returns the indices of the first word of each key, followed by the number of words.
+/// `words` are sorted by their key id
+fn word_indices(words: &[(usize, Word)], keys: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity((keys + 1) * 4);
+    let mut word = 0;
+
+    for key in 0..=keys {
+        while word < words.len() && words[word].0 < key {
+            word += 1;
+        }
+        bytes.extend_from_slice(&(word as i32).to_le_bytes());
+    }
+
+    bytes
+}
+
+/// Returns the contents of `word.inf` and `word.dat`. Every array of `word.inf` ends with a
+/// sentinel, so the features of a word end where the ones of the next word start
+fn word_info(words: &[(usize, Word)]) -> (Vec<u8>, Vec<u8>) {
+    let mut data = Vec::new();
+    let mut offsets = Vec::with_capacity(words.len() + 1);
+
+    for (_, word) in words {
+        offsets.push(data.len() as i32);
+        data.extend(word.feature.encode_utf16());
+    }
+    offsets.push(data.len() as i32);
+
+    let mut info = Vec::with_capacity((words.len() + 1) * 10);
+    for offset in offsets {
+        info.extend_from_slice(&offset.to_le_bytes());
+    }
+    for field in [
+        |w: &Word| w.left_id,
+        |w: &Word| w.right_id,
+        |w: &Word| w.cost,
+    ] {
+        for (_, word) in words {
+            info.extend_from_slice(&field(word).to_le_bytes());
+        }
+        info.extend_from_slice(&0i16.to_le_bytes());
+    }
+
+    (info, utf16_bytes(&data))
+}
+
+/// The connection costs between the right id of a word and the left id of the word after it
+struct Matrix {
+    /// Number of right ids, the first index of the matrix
+    right_ids: usize,
+    /// Number of left ids, the second index of the matrix
+    left_ids: usize,
+    costs: Vec<i16>,
+}
+
+impl Matrix {
+    /// Reads `matrix.def`, a line with the number of right and left ids followed by lines
+    /// `right id left id cost`, one for each pair of ids
+    fn read(file: &Path, progress: &mut impl FnMut(CompileProgress)) -> Result<Self, CompileError> {
+        let mut matrix: Option<Matrix> = None;
+        let mut defined = 0;
+
+        read_lines(file, progress, |line_nr, line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.is_empty() {
+                return Ok(());
+            }
+            let number = |i: usize| parse_number::<i16>(columns[i], file, line_nr);
+
+            let matrix = match &mut matrix {
+                Some(matrix) if columns.len() == 3 => matrix,
+                None if columns.len() == 2 => {
+                    let size = |i| {
+                        number(i).and_then(|size| match size {
+                            size if size > 0 => Ok(size as usize),
+                            _ => Err(CompileError::Number {
+                                file: file.to_path_buf(),
+                                line: line_nr,
+                                value: columns[i].to_string(),
+                            }),
+                        })
+                    };
+                    let (right_ids, left_ids) = (size(0)?, size(1)?);
+                    matrix = Some(Matrix {
+                        right_ids,
+                        left_ids,
+                        costs: vec![0; right_ids * left_ids],
+                    });
+                    return Ok(());
+                }
+                _ => {
+                    return Err(CompileError::Columns {
+                        file: file.to_path_buf(),
+                        line: line_nr,
+                        count: columns.len(),
+                    })
+                }
+            };
+
+            let (right, left) = (number(0)?, number(1)?);
+            check_context_id(right, matrix.right_ids, file, line_nr)?;
+            check_context_id(left, matrix.left_ids, file, line_nr)?;
+
+            // igo looks up the cost of a right id and a left id at `left * right ids + right`
+            matrix.costs[left as usize * matrix.right_ids + right as usize] = number(2)?;
+            defined += 1;
+            Ok(())
+        })?;
+
+        let matrix = matrix.ok_or_else(|| CompileError::Columns {
+            file: file.to_path_buf(),
+            line: 1,
+            count: 0,
+        })?;
+        if defined != matrix.costs.len() {
+            return Err(CompileError::IncompleteMatrix {
+                expected: matrix.costs.len(),
+                found: defined,
+            });
+        }
+
+        Ok(matrix)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.costs.len() * 2);
+        bytes.extend_from_slice(&(self.right_ids as i32).to_le_bytes());
+        bytes.extend_from_slice(&(self.left_ids as i32).to_le_bytes());
+        for cost in &self.costs {
+            bytes.extend_from_slice(&cost.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+/// The character categories of `char.def`
+struct CharDef {
+    /// Names of the categories in the order of their definition
+    names: Vec<String>,
+    /// Length, invoke and group of each category
+    settings: Vec<(i32, bool, bool)>,
+    /// Index of the category of each UTF-16 code unit
+    codes: Vec<i32>,
+    /// Bits of the categories of each code unit. igo joins unknown characters of compatible
+    /// categories, which share a bit
+    masks: Vec<i32>,
+}
+
+impl CharDef {
+    /// Reads `char.def`. Category lines are `name invoke group length`, code lines are
+    /// `0xXXXX[..0xXXXX] name [compatible names...]`. Text after `#` is a comment. Code units
+    /// without a code line belong to `DEFAULT`, code points above U+FFFF are skipped as igo only
+    /// categorizes UTF-16 code units
+    fn read(file: &Path, progress: &mut impl FnMut(CompileProgress)) -> Result<Self, CompileError> {
+        let mut names: Vec<String> = Vec::new();
+        let mut settings = Vec::new();
+        let mut ranges = Vec::new();
+
+        read_lines(file, progress, |line_nr, line| {
+            let line = line.split('#').next().unwrap_or_default();
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.is_empty() {
+                return Ok(());
+            }
+
+            if !columns[0].starts_with("0x") {
+                if columns.len() < 4 {
+                    return Err(CompileError::Columns {
+                        file: file.to_path_buf(),
+                        line: line_nr,
+                        count: columns.len(),
+                    });
+                }
+                let number = |i: usize| parse_number::<i32>(columns[i], file, line_nr);
+                let setting = (number(3)?, number(1)? != 0, number(2)? != 0);
+
+                match names.iter().position(|name| name == columns[0]) {
+                    Some(i) => settings[i] = setting,
+                    None => {
+                        names.push(columns[0].to_string());
+                        settings.push(setting);
+                    }
+                }
+                return Ok(());
+            }
+
+            if columns.len() < 2 {
+                return Err(CompileError::Columns {
+                    file: file.to_path_buf(),
+                    line: line_nr,
+                    count: columns.len(),
+                });
+            }
+
+            let code = |value: &str| {
+                u32::from_str_radix(value.trim_start_matches("0x"), 16).map_err(|_| {
+                    CompileError::Number {
+                        file: file.to_path_buf(),
+                        line: line_nr,
+                        value: value.to_string(),
+                    }
+                })
+            };
+            let (start, end) = match columns[0].split_once("..") {
+                Some((start, end)) => (code(start)?, code(end)?),
+                None => (code(columns[0])?, code(columns[0])?),
+            };
+
+            let mut categories = Vec::with_capacity(columns.len() - 1);
+            for name in &columns[1..] {
+                let category = names
+                    .iter()
+                    .position(|known| known == name)
+                    .ok_or_else(|| CompileError::UnknownCategory {
+                        file: file.to_path_buf(),
+                        line: line_nr,
+                        name: name.to_string(),
+                    })?;
+                categories.push(category);
+            }
+            ranges.push((start, end, categories));
+            Ok(())
+        })?;
+
+        if names.len() > 32 {
+            return Err(CompileError::TooManyCategories(names.len()));
+        }
+        let default = names
+            .iter()
+            .position(|name| name == "DEFAULT")
+            .ok_or_else(|| CompileError::MissingCategory("DEFAULT".to_string()))?;
+
+        let mut codes = vec![default as i32; CODE_LIMIT];
+        let mut masks = vec![1 << default; CODE_LIMIT];
+        for (start, end, categories) in ranges {
+            let mask = categories.iter().fold(0, |mask, i| mask | 1 << i);
+            for code in start as usize..=(end as usize).min(CODE_LIMIT - 1) {
+                codes[code] = categories[0] as i32;
+                masks[code] = mask;
+            }
+        }
+
+        Ok(CharDef {
+            names,
+            settings,
+            codes,
+            masks,
+        })
+    }
+
+    /// Returns `char.category`, the key id, length, invoke and group of each category
+    fn category_bytes(&self, key_ids: &[usize]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.names.len() * 16);
+        for (id, (length, invoke, group)) in key_ids.iter().zip(&self.settings) {
+            for value in [*id as i32, *length, *invoke as i32, *group as i32] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Returns `code2category`, the category index and the category mask of each code unit
+    fn code_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(CODE_LIMIT * 8);
+        for value in self.codes.iter().chain(&self.masks) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+/// Writes the dictionary files into `output`
+struct DictionaryWriter<'a, F> {
+    output: &'a Path,
+    progress: &'a mut F,
+}
+
+impl<F: FnMut(CompileProgress)> DictionaryWriter<'_, F> {
+    fn write(&mut self, name: &str, bytes: &[u8]) -> Result<(), CompileError> {
+        let file = self.output.join(name);
+        let io_error = |source| CompileError::Io {
+            file: file.clone(),
+            source,
+        };
+
+        let mut writer = BufWriter::new(File::create(&file).map_err(io_error)?);
+        writer.write_all(bytes).map_err(io_error)?;
+        writer.flush().map_err(io_error)?;
+
+        (self.progress)(CompileProgress::Written {
+            file: &file,
+            bytes: bytes.len(),
+        });
+        Ok(())
+    }
+}
+
+/// Calls `f` with the number and content of each line of `file`, reporting the progress
+fn read_lines<F>(
+    file: &Path,
+    progress: &mut impl FnMut(CompileProgress),
+    mut f: F,
+) -> Result<(), CompileError>
+where
+    F: FnMut(usize, &str) -> Result<(), CompileError>,
+{
+    let io_error = |source| CompileError::Io {
+        file: file.to_path_buf(),
+        source,
+    };
+    let reader = BufReader::new(File::open(file).map_err(io_error)?);
+    let mut lines = 0;
+
+    for line in reader.lines() {
+        let line = line.map_err(io_error)?;
+        lines += 1;
+        f(lines, line.trim_end_matches('\r'))?;
+
+        if lines % PROGRESS_LINES == 0 {
+            progress(CompileProgress::Reading { file, lines });
+        }
+    }
+
+    if lines % PROGRESS_LINES != 0 {
+        progress(CompileProgress::Reading { file, lines });
+    }
+    Ok(())
+}
+
+/// Splits the first `count` columns off a CSV line and returns them unquoted, along with the
+/// rest of the line after the comma following them. Returns `None` if the line has fewer columns
+fn split_columns(line: &str, count: usize) -> Option<(Vec<String>, &str)> {
+    let mut columns = Vec::with_capacity(count);
+    let mut rest = line;
+
+    while columns.len() < count {
+        let (column, next) = match rest.strip_prefix('"') {
+            Some(quoted) => {
+                let mut column = String::new();
+                let mut chars = quoted.char_indices().peekable();
+                loop {
+                    match chars.next()? {
+                        (_, '"') if chars.peek().map(|(_, c)| *c) == Some('"') => {
+                            column.push('"');
+                            chars.next();
+                        }
+                        (i, '"') => break (column, &quoted[i + 1..]),
+                        (_, c) => column.push(c),
+                    }
+                }
+            }
+            None => {
+                let end = rest.find(',').unwrap_or(rest.len());
+                (rest[..end].to_string(), &rest[end..])
+            }
+        };
+
+        columns.push(column);
+        rest = match next.strip_prefix(',') {
+            Some(next) => next,
+            None if next.is_empty() && columns.len() == count => next,
+            None => return None,
+        };
+    }
+
+    Some((columns, rest))
+}
+
+/// Returns an error if `id` is not below `limit`, the number of ids defined by `matrix.def`
+fn check_context_id(id: i16, limit: usize, file: &Path, line: usize) -> Result<(), CompileError> {
+    if id < 0 || id as usize >= limit {
+        return Err(CompileError::ContextId {
+            file: file.to_path_buf(),
+            line,
+            id,
+            limit,
+        });
+    }
+    Ok(())
+}
+
+fn parse_number<T: std::str::FromStr>(
+    value: &str,
+    file: &Path,
+    line: usize,
+) -> Result<T, CompileError> {
+    value.trim().parse().map_err(|_| CompileError::Number {
+        file: file.to_path_buf(),
+        line,
+        value: value.to_string(),
+    })
+}
+
+fn utf16_bytes(units: &[u16]) -> Vec<u8> {
+    units.iter().flat_map(|unit| unit.to_le_bytes()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn keys(words: &[&str]) -> Vec<Vec<u16>> {
+        let mut keys: Vec<Vec<u16>> = words.iter().map(|w| w.encode_utf16().collect()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    fn utf16(text: &str) -> Vec<u16> {
+        text.encode_utf16().collect()
+    }
+
+    #[test]
+    fn csv_columns() {
+        assert_eq!(
+            split_columns("\"a,\"\"b\",1,2,3,x,\"y,z\"", 4),
+            Some((
+                vec!["a,\"b".to_string(), "1".into(), "2".into(), "3".into()],
+                "x,\"y,z\""
+            ))
+        );
+        assert_eq!(
+            split_columns("a,1,2,3", 4),
+            Some((
+                vec!["a".to_string(), "1".into(), "2".into(), "3".into()],
+                ""
+            ))
+        );
+        assert_eq!(split_columns("a,1,2", 4), None);
+        assert_eq!(split_columns("\"a,1,2,3", 4), None);
+    }
+
+    /// Writes the sources into a new directory and returns it
+    fn sources(name: &str, lex: &str, matrix: &str, char_def: &str, unk: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "igo-unidic-compile-{}-{}",
+            name,
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        for (file, content) in [
+            (LEX_FILE, lex),
+            (MATRIX_FILE, matrix),
+            (CHAR_FILE, char_def),
+            (UNK_FILE, unk),
+        ] {
+            fs::write(dir.join(file), content).unwrap();
+        }
+        dir
+    }
+
+    const LEX: &str = "東京,1,1,3000,名詞,固有名詞,地名,一般\n\
+        東京都,1,2,2000,名詞,固有名詞,地名,一般\n\
+        \"，\",0,0,100,補助記号,読点,*,*\n\
+        東京,2,1,4000,名詞,普通名詞,一般,*\n";
+    const MATRIX: &str =
+        "3 3\n0 0 0\n0 1 1\n0 2 2\n1 0 10\n1 1 11\n1 2 12\n2 0 20\n2 1 21\n2 2 -22\n";
+    const CHAR_DEF: &str = "# categories\n\
+        DEFAULT 0 1 0\n\
+        SPACE 0 1 0\n\
+        KANJI 0 0 2 # comment\n\
+        HIRAGANA 1 1 0\n\
+        0x0020 SPACE\n\
+        0x3041..0x309F HIRAGANA\n\
+        0x4E00..0x9FFF KANJI\n\
+        0x3005 KANJI HIRAGANA\n\
+        0x20000..0x2A6DF KANJI\n";
+    const UNK: &str = "DEFAULT,0,0,5000,補助記号,一般,*,*\n\
+        SPACE,0,0,100,空白,*,*,*\n\
+        KANJI,1,1,8000,名詞,普通名詞,一般,*\n\
+        HIRAGANA,2,2,9000,名詞,普通名詞,一般,*\n";
+
+    fn ints(bytes: &[u8]) -> Vec<i32> {
+        bytes
+            .chunks(4)
+            .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    }
+
+    fn shorts(bytes: &[u8]) -> Vec<i16> {
+        bytes
+            .chunks(2)
+            .map(|c| i16::from_le_bytes([c[0], c[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn compile() {
+        let dir = sources("compile", LEX, MATRIX, CHAR_DEF, UNK);
+        let output = dir.join("dict");
+        let mut written = Vec::new();
+        let summary = compile_dictionary(&dir, &output, |progress| {
+            if let CompileProgress::Written { file, .. } = progress {
+                written.push(file.file_name().unwrap().to_string_lossy().into_owned());
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            summary,
+            CompileSummary {
+                words: 8,
+                keys: 7,
+                categories: 4,
+                left_ids: 3,
+                right_ids: 3,
+            }
+        );
+        let mut files: Vec<&str> = crate::DICTIONARY_FILES.to_vec();
+        files.sort_unstable();
+        written.sort_unstable();
+        assert_eq!(written, files);
+        assert!(crate::check_dictionary(&output).is_ok());
+
+        let read = |name: &str| fs::read(output.join(name)).unwrap();
+        let keys = keys(&[
+            "東京",
+            "東京都",
+            "，",
+            "DEFAULT",
+            "SPACE",
+            "KANJI",
+            "HIRAGANA",
+        ]);
+        let id = |key: &str| keys.iter().position(|k| *k == utf16(key)).unwrap();
+
+        // Both words of 東京 follow each other in the order of lex.csv
+        let indices = ints(&read("word.ary.idx"));
+        assert_eq!(indices.len(), 8);
+        assert_eq!(indices[7], 8);
+        let tokyo = indices[id("東京")] as usize;
+        assert_eq!(indices[id("東京") + 1] as usize, tokyo + 2);
+
+        let info = read("word.inf");
+        assert_eq!(info.len(), 9 * 10);
+        let offsets = ints(&info[..36]);
+        let left_ids = shorts(&info[36..54]);
+        let right_ids = shorts(&info[54..72]);
+        let costs = shorts(&info[72..]);
+        assert_eq!(
+            (left_ids[tokyo], right_ids[tokyo], costs[tokyo]),
+            (1, 1, 3000)
+        );
+        assert_eq!(costs[tokyo + 1], 4000);
+
+        let data: Vec<u16> = shorts(&read("word.dat"))
+            .iter()
+            .map(|u| *u as u16)
+            .collect();
+        let feature = |word: usize| {
+            String::from_utf16(&data[offsets[word] as usize..offsets[word + 1] as usize]).unwrap()
+        };
+        assert_eq!(feature(tokyo), "名詞,固有名詞,地名,一般");
+        assert_eq!(feature(indices[id("，")] as usize), "補助記号,読点,*,*");
+        assert_eq!(offsets[8] as usize, data.len());
+
+        let matrix = read("matrix.bin");
+        assert_eq!(ints(&matrix[..8]), vec![3, 3]);
+        let costs = shorts(&matrix[8..]);
+        // The cost of right id 1 followed by left id 2 is at 2 * 3 + 1
+        assert_eq!(costs[2 * 3 + 1], 12);
+        assert_eq!(costs[2 * 3 + 2], -22);
+
+        let categories = ints(&read("char.category"));
+        assert_eq!(
+            categories,
+            vec![
+                id("DEFAULT") as i32,
+                0,
+                0,
+                1,
+                id("SPACE") as i32,
+                0,
+                0,
+                1,
+                id("KANJI") as i32,
+                2,
+                0,
+                0,
+                id("HIRAGANA") as i32,
+                0,
+                1,
+                1,
+            ]
+        );
+
+        let codes = ints(&read("code2category"));
+        assert_eq!(codes.len(), CODE_LIMIT * 2);
+        let category = |c: char| codes[c as usize];
+        let mask = |c: char| codes[CODE_LIMIT + c as usize];
+        assert_eq!(category('a'), 0);
+        assert_eq!(category(' '), 1);
+        assert_eq!(category('東'), 2);
+        assert_eq!((category('あ'), mask('あ')), (3, 1 << 3));
+        assert_eq!((category('々'), mask('々')), (2, 1 << 2 | 1 << 3));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn compiled_dictionary_is_loaded() {
+        let padded = |lines: &str| -> String {
+            lines
+                .lines()
+                .map(|line| format!("{},*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*\n", line))
+                .collect()
+        };
+        let dir = sources("load", &padded(LEX), MATRIX, CHAR_DEF, &padded(UNK));
+        let output = dir.join("dict");
+        compile_dictionary(&dir, &output, |_| {}).unwrap();
+
+        let parser = crate::Parser::new(output.to_str().unwrap()).unwrap();
+        let text = "東京都，東京";
+        let surfaces: Vec<_> = parser.parse(text).iter().map(|m| m.surface).collect();
+        assert_eq!(surfaces, vec!["東京都", "，", "東京"]);
+
+        // The connection cost of right id `r` and left id `l` is `10 * r + l`, the second 東京
+        // keeps the cheaper of its two words
+        let analysis = &parser.parse_nbest(text, 1)[0];
+        assert_eq!(analysis.cost, 1 + 2000 + 20 + 100 + 1 + 3000 + 10);
+        assert_eq!(
+            analysis.morphemes[0].word_class,
+            crate::WordClass::Noun(crate::NounType::Proper)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn validation() {
+        let compile = |name: &str, lex: &str, matrix: &str, unk: &str| {
+            let dir = sources(name, lex, matrix, CHAR_DEF, unk);
+            let result = compile_dictionary(&dir, dir.join("dict"), |_| {});
+            assert!(!dir.join("dict").exists());
+            fs::remove_dir_all(&dir).unwrap();
+            result.unwrap_err()
+        };
+
+        let err = compile("context", "東京,3,1,3000,名詞\n", MATRIX, UNK);
+        assert!(matches!(
+            err,
+            CompileError::ContextId {
+                line: 1,
+                id: 3,
+                limit: 3,
+                ..
+            }
+        ));
+
+        let err = compile("cost", "東京,1,1,40000,名詞\n", MATRIX, UNK);
+        assert!(matches!(err, CompileError::Number { line: 1, .. }));
+
+        let err = compile("columns", "東京,1,1,3000\n", MATRIX, UNK);
+        assert!(matches!(
+            err,
+            CompileError::Columns {
+                line: 1,
+                count: 4,
+                ..
+            }
+        ));
+
+        let err = compile("matrix", LEX, "3 3\n0 0 0\n", UNK);
+        assert!(matches!(
+            err,
+            CompileError::IncompleteMatrix {
+                expected: 9,
+                found: 1
+            }
+        ));
+
+        let err = compile("missing", LEX, MATRIX, "DEFAULT,0,0,5000,補助記号\n");
+        assert!(matches!(err, CompileError::MissingCategory(name) if name == "SPACE"));
+
+        let err = compile(
+            "unknown",
+            LEX,
+            MATRIX,
+            &format!("{}\nKATAKANA,0,0,1,名詞\n", UNK),
+        );
+        assert!(matches!(
+            err,
+            CompileError::UnknownCategory { line: 6, ref name, .. } if name == "KATAKANA"
+        ));
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{AdjectiveType, ConjungationForm, Morpheme, Parser, ParticleType, VerbType, WordClass};
+
+/// A grammatical transformation applied to a word by an auxilary, particle or helper verb
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Transformation {
+    /// せる, させる
+    Causative,
+    /// れる, られる. Also used for the potential and honorific meaning
+    Passive,
+    /// ない, ぬ
+    Negative,
+    /// た
+    Past,
+    /// ます, です
+    Polite,
+    /// たい
+    Desiderative,
+    /// う, よう
+    Volitional,
+    /// まい
+    NegativeVolitional,
+    /// て, で
+    Te,
+    /// ば, たら
+    Conditional,
+    /// ている
+    Progressive,
+    /// てしまう
+    Completion,
+}
+
+/// A single step of a [`Deinflection`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct InflectionStep<'dict, 'input> {
+    pub transformation: Transformation,
+    /// The morpheme applying the transformation. Its conjungation form tells how the following
+    /// step attaches to it
+    pub morpheme: Morpheme<'dict, 'input>,
+}
+
+/// A conjungated verb or adjective, split into its underlying word and the transformations
+/// applied to it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deinflection<'dict, 'input> {
+    /// The dictionary form of the word, like 食べる for 食べさせられなかった
+    pub base: &'dict str,
+    /// The morpheme of the conjungated word itself
+    pub head: Morpheme<'dict, 'input>,
+    /// The transformations in the order they were applied
+    pub steps: Vec<InflectionStep<'dict, 'input>>,
+}
+
+impl<'dict, 'input> Deinflection<'dict, 'input> {
+    /// Returns the transformations in the order they were applied
+    pub fn transformations(&self) -> Vec<Transformation> {
+        self.steps.iter().map(|step| step.transformation).collect()
+    }
+
+    /// Returns the amount of morphemes the deinflection consists of, including its head
+    pub fn morpheme_count(&self) -> usize {
+        self.steps.len() + 1
+    }
+}
+
+impl Parser {
+    /// Parses `text` and deinflects the verb or adjective it starts with. See [`deinflect`]
+    pub fn deinflect<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+    ) -> Option<Deinflection<'dict, 'text>> {
+        deinflect(&self.parse_lenient(text))
+    }
+}
+
+/// Deinflects the verb or adjective at the start of `morphemes`. Following auxilaries,
+/// particles and helper verbs are consumed as long as they conjungate the word. Returns `None`
+/// if `morphemes` doesn't start with a verb or adjective.
+///
+/// 食べ|させ|られ|なかっ|た results in 食べる with the transformations causative, passive,
+/// negative and past.
+pub fn deinflect<'dict, 'input>(
+    morphemes: &[Morpheme<'dict, 'input>],
+) -> Option<Deinflection<'dict, 'input>> {
+    let (head, rest) = morphemes.split_first()?;
+
+    let is_head = match head.word_class {
+        WordClass::Verb(VerbType::Auxilary(_)) | WordClass::Verb(VerbType::Unknown(_)) => false,
+        WordClass::Verb(_) | WordClass::Adjective(AdjectiveType::I) => true,
+        _ => false,
+    };
+    if !is_head {
+        return None;
+    }
+
+    let mut steps: Vec<InflectionStep> = Vec::new();
+    for morph in rest {
+        let prev = steps.last().map(|step| step.transformation);
+        let transformation = match transformation(morph, prev) {
+            Some(transformation) => transformation,
+            None => break,
+        };
+
+        steps.push(InflectionStep {
+            transformation,
+            morpheme: *morph,
+        });
+    }
+
+    Some(Deinflection {
+        base: head.lexeme,
+        head: *head,
+        steps,
+    })
+}
+
+/// Returns the transformation `morph` applies to the preceding morphemes, if any
+fn transformation(morph: &Morpheme, prev: Option<Transformation>) -> Option<Transformation> {
+    let lemma = morph.features().lemma;
+
+    match morph.word_class {
+        // Causatives conjungate like 下一段 verbs and う and よう don't conjungate at all, so
+        // their cType only names a row or nothing and their lemma has to tell them apart
+        WordClass::Verb(VerbType::Auxilary(name)) => Some(match (name, lemma) {
+            ("サ行", "せる") | ("サ行", "させる") | ("マ行", "しめる") => {
+                Transformation::Causative
+            }
+            ("レル", _) => Transformation::Passive,
+            ("ナイ", _) | ("ヌ", _) => Transformation::Negative,
+            ("タ", _) if morph.conjungation.form == ConjungationForm::Conditional => {
+                Transformation::Conditional
+            }
+            ("タ", _) => Transformation::Past,
+            ("マス", _) | ("デス", _) => Transformation::Polite,
+            ("タイ", _) => Transformation::Desiderative,
+            ("マイ", _) | (_, "まい") => Transformation::NegativeVolitional,
+            (_, "う") | (_, "よう") => Transformation::Volitional,
+            _ => return None,
+        }),
+        WordClass::Particle(ParticleType::Conjungtion) => match morph.surface {
+            "て" | "で" => Some(Transformation::Te),
+            "ば" => Some(Transformation::Conditional),
+            _ => None,
+        },
+        // 高くない uses the adjective ない instead of the auxilary
+        WordClass::Adjective(AdjectiveType::I) if lemma == "無い" => {
+            Some(Transformation::Negative)
+        }
+        WordClass::Verb(_) if prev == Some(Transformation::Te) => match lemma {
+            "居る" => Some(Transformation::Progressive),
+            "仕舞う" => Some(Transformation::Completion),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::morph;
+
+    const VERB: &str = "動詞,一般,*,*";
+    const HELPER: &str = "動詞,非自立可能,*,*";
+    const AUXILARY: &str = "助動詞,*,*,*";
+    const PARTICLE: &str = "助詞,接続助詞,*,*";
+    const NOUN: &str = "名詞,普通名詞,一般,*";
+
+    /// Deinflects morphemes given as surface, part of speech, cType, cForm and lemma, returning
+    /// the base and the transformations
+    fn deinflected(
+        words: &[(&str, &str, &str, &str, &str)],
+    ) -> Option<(String, Vec<Transformation>)> {
+        let features: Vec<String> = words
+            .iter()
+            .map(|(_, pos, c_type, c_form, lemma)| {
+                format!(
+                    "{},{},{},*,{},*,*,{},*,和",
+                    pos, c_type, c_form, lemma, lemma
+                )
+            })
+            .collect();
+
+        let mut start = 0;
+        let morphemes: Vec<_> = words
+            .iter()
+            .zip(&features)
+            .map(|((surface, ..), feature)| {
+                let morph = morph(surface, start, feature);
+                start += surface.len();
+                morph
+            })
+            .collect();
+
+        deinflect(&morphemes).map(|d| (d.base.to_string(), d.transformations()))
+    }
+
+    #[test]
+    fn auxilary_chain() {
+        use Transformation::*;
+
+        let words = [
+            ("食べ", VERB, "下一段-バ行", "未然形-一般", "食べる"),
+            ("させ", AUXILARY, "下一段-サ行", "未然形-一般", "させる"),
+            ("られ", AUXILARY, "助動詞-レル", "未然形-一般", "られる"),
+            ("なかっ", AUXILARY, "助動詞-ナイ", "連用形-促音便", "ない"),
+            ("た", AUXILARY, "助動詞-タ", "終止形-一般", "た"),
+        ];
+        assert_eq!(
+            deinflected(&words),
+            Some((
+                "食べる".to_string(),
+                vec![Causative, Passive, Negative, Past]
+            ))
+        );
+    }
+
+    #[test]
+    fn godan_causative_and_passive() {
+        use Transformation::*;
+
+        let causative = [
+            ("書か", VERB, "五段-カ行", "未然形-一般", "書く"),
+            ("せ", AUXILARY, "下一段-サ行", "連用形-一般", "せる"),
+            ("ます", AUXILARY, "助動詞-マス", "終止形-一般", "ます"),
+        ];
+        assert_eq!(
+            deinflected(&causative),
+            Some(("書く".to_string(), vec![Causative, Polite]))
+        );
+
+        let passive = [
+            ("書か", VERB, "五段-カ行", "未然形-一般", "書く"),
+            ("れる", AUXILARY, "助動詞-レル", "終止形-一般", "れる"),
+        ];
+        assert_eq!(
+            deinflected(&passive),
+            Some(("書く".to_string(), vec![Passive]))
+        );
+    }
+
+    #[test]
+    fn te_forms_and_helper_verbs() {
+        use Transformation::*;
+
+        let words = [
+            ("食べ", VERB, "下一段-バ行", "連用形-一般", "食べる"),
+            ("て", PARTICLE, "*", "*", "て"),
+            ("い", HELPER, "上一段-ア行", "連用形-一般", "居る"),
+            ("た", AUXILARY, "助動詞-タ", "終止形-一般", "た"),
+        ];
+        assert_eq!(
+            deinflected(&words),
+            Some(("食べる".to_string(), vec![Te, Progressive, Past]))
+        );
+    }
+
+    #[test]
+    fn words_without_chain() {
+        // The following noun isn't part of the conjungation
+        let words = [
+            ("食べる", VERB, "下一段-バ行", "連体形-一般", "食べる"),
+            ("本", NOUN, "*", "*", "本"),
+        ];
+        assert_eq!(deinflected(&words), Some(("食べる".to_string(), vec![])));
+
+        // A helper verb only continues a te form
+        let words = [
+            ("食べる", VERB, "下一段-バ行", "終止形-一般", "食べる"),
+            ("居る", HELPER, "上一段-ア行", "終止形-一般", "居る"),
+        ];
+        assert_eq!(deinflected(&words), Some(("食べる".to_string(), vec![])));
+
+        let words = [("本", NOUN, "*", "*", "本")];
+        assert_eq!(deinflected(&words), None);
+        assert_eq!(deinflected(&[]), None);
+    }
+}
@@ -0,0 +1,481 @@
+//! Reads the binary dictionary layout of igo's `BuildDic` tool, which [`Parser`](crate::Parser)
+//! uses to build its own lattice.
+//!
+//! All numbers are little endian and all text is UTF-16:
+//!
+//! - `word2id`: a double array trie of all surfaces and unknown word categories, mapping them to
+//!   their key id, see [`Trie`]
+//! - `word.ary.idx`: for each key id, the index of its first word, followed by the number of words
+//! - `word.inf`: for each word the start of its features in `word.dat`, then for each word its
+//!   left id, its right id and its cost, each followed by a sentinel
+//! - `word.dat`: the features of all words
+//! - `matrix.bin`: the number of right and left ids followed by the connection costs. The cost of
+//!   a right id followed by a left id is at `left * right ids + right`
+//! - `char.category`: the key id, length, invoke and group of each character category
+//! - `code2category`: for each UTF-16 code unit the index of its category, then its category mask
+
+use std::{
+    convert::TryFrom,
+    fs::File,
+    ops::{Deref, Range},
+    path::Path,
+    sync::OnceLock,
+};
+
+use memmap2::Mmap;
+
+use crate::{check_dictionary, trie::Trie, DictionaryError, DICTIONARY_FILES};
+
+/// Number of code units categorized by `code2category`
+const CODES: usize = 0x10000;
+
+/// Returns the little endian `i32` at `index` of `bytes`, which has to be in bounds
+pub(crate) fn i32_at(bytes: &[u8], index: usize) -> i32 {
+    let i = index * 4;
+    i32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
+}
+
+/// Returns the little endian `i16` at `index` of `bytes`, which has to be in bounds
+pub(crate) fn i16_at(bytes: &[u8], index: usize) -> i16 {
+    i16::from_le_bytes([bytes[index * 2], bytes[index * 2 + 1]])
+}
+
+/// Returns the little endian `u16` at `index` of `bytes`, which has to be in bounds
+pub(crate) fn u16_at(bytes: &[u8], index: usize) -> u16 {
+    u16::from_le_bytes([bytes[index * 2], bytes[index * 2 + 1]])
+}
+
+/// The contents of a dictionary file
+pub(crate) enum Data {
+    Mapped(Mmap),
+    Static(&'static [u8]),
+}
+
+impl Deref for Data {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Data::Mapped(map) => map,
+            Data::Static(bytes) => bytes,
+        }
+    }
+}
+
+/// The contents of the files of a dictionary directory, for example embedded into the binary with
+/// `include_bytes!`
+#[derive(Clone, Copy, Debug)]
+pub struct DictionaryBytes {
+    pub word2id: &'static [u8],
+    pub word_dat: &'static [u8],
+    pub word_ary_idx: &'static [u8],
+    pub word_inf: &'static [u8],
+    pub matrix_bin: &'static [u8],
+    pub char_category: &'static [u8],
+    pub code2category: &'static [u8],
+}
+
+/// A character category of `char.def`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Category {
+    /// Key id of the category's name, under which its unknown words are stored
+    pub(crate) id: usize,
+    /// Number of lengths up to which unknown words are created
+    pub(crate) length: usize,
+    /// Whether unknown words are created even if known words start at the same position
+    pub(crate) invoke: bool,
+    /// Whether an unknown word covering all compatible characters is created
+    pub(crate) group: bool,
+}
+
+/// A dictionary in igo's binary layout
+pub(crate) struct Dictionary {
+    trie: Data,
+    data: Data,
+    indices: Data,
+    info: Data,
+    matrix: Data,
+    categories: Data,
+    codes: Data,
+    words: usize,
+    right_ids: usize,
+    /// Key id of the `SPACE` category, whose unknown words are skipped
+    space: Option<usize>,
+    /// Features of the words decoded so far
+    features: Vec<OnceLock<Box<str>>>,
+}
+
+impl Dictionary {
+    /// Maps the dictionary files in `path` into memory. The files must not be modified while the
+    /// dictionary is used
+    pub(crate) fn load(path: &Path) -> Result<Self, DictionaryError> {
+        check_dictionary(path)?;
+
+        let map = |name: &str| {
+            let file = path.join(name);
+            // SAFETY: mapping is only unsound if the file is changed while mapped, which
+            // `Parser::new` documents as a requirement
+            File::open(&file)
+                .and_then(|handle| unsafe { Mmap::map(&handle) })
+                .map(Data::Mapped)
+                .map_err(|source| DictionaryError::Io { file, source })
+        };
+        let files = [
+            map(DICTIONARY_FILES[0])?,
+            map(DICTIONARY_FILES[1])?,
+            map(DICTIONARY_FILES[2])?,
+            map(DICTIONARY_FILES[3])?,
+            map(DICTIONARY_FILES[4])?,
+            map(DICTIONARY_FILES[5])?,
+            map(DICTIONARY_FILES[6])?,
+        ];
+
+        Self::from_data(files).map_err(|name| DictionaryError::Corrupt(path.join(name)))
+    }
+
+    /// Creates a dictionary of the contents of its files
+    pub(crate) fn from_bytes(bytes: DictionaryBytes) -> Result<Self, DictionaryError> {
+        let files = [
+            bytes.word2id,
+            bytes.word_dat,
+            bytes.word_ary_idx,
+            bytes.word_inf,
+            bytes.matrix_bin,
+            bytes.char_category,
+            bytes.code2category,
+        ];
+        Self::from_data(files.map(Data::Static)).map_err(DictionaryError::CorruptBytes)
+    }
+
+    /// Creates a dictionary of the contents of the files in the order of [`DICTIONARY_FILES`].
+    /// Returns the name of the first file which is inconsistent
+    pub(crate) fn from_data(files: [Data; 7]) -> Result<Self, &'static str> {
+        let [trie, data, indices, info, matrix, categories, codes] = files;
+
+        let keys = Trie::new(&trie).ok_or(DICTIONARY_FILES[0])?.keys();
+
+        if info.len() % 10 != 0 || info.is_empty() {
+            return Err(DICTIONARY_FILES[3]);
+        }
+        let words = info.len() / 10 - 1;
+        let offsets = (0..=words).map(|word| i32_at(&info, word));
+        if !is_ascending(offsets, data.len() / 2) {
+            return Err(DICTIONARY_FILES[3]);
+        }
+
+        let starts = (0..indices.len() / 4).map(|key| i32_at(&indices, key));
+        if indices.len() != (keys + 1) * 4 || !is_ascending(starts, words) {
+            return Err(DICTIONARY_FILES[2]);
+        }
+
+        if matrix.len() < 8 {
+            return Err(DICTIONARY_FILES[4]);
+        }
+        let size = |i| usize::try_from(i32_at(&matrix, i)).ok();
+        let (right_ids, left_ids) = match (size(0), size(1)) {
+            (Some(right_ids), Some(left_ids)) if matrix.len() == 8 + right_ids * left_ids * 2 => {
+                (right_ids, left_ids)
+            }
+            _ => return Err(DICTIONARY_FILES[4]),
+        };
+
+        let mut dictionary = Dictionary {
+            trie,
+            data,
+            indices,
+            info,
+            matrix,
+            categories,
+            codes,
+            words,
+            right_ids,
+            space: None,
+            features: Vec::new(),
+        };
+
+        // The lattice looks up connection costs without checking the ids
+        let valid = |id: i16, limit: usize| id >= 0 && (id as usize) < limit;
+        if !(0..words).all(|word| {
+            valid(dictionary.left_id(word), left_ids) && valid(dictionary.right_id(word), right_ids)
+        }) {
+            return Err(DICTIONARY_FILES[3]);
+        }
+
+        let count = dictionary.categories.len() / 16;
+        if dictionary.categories.len() % 16 != 0
+            || !(0..count).all(|i| {
+                let (id, length) = (
+                    i32_at(&dictionary.categories, i * 4),
+                    i32_at(&dictionary.categories, i * 4 + 1),
+                );
+                id >= 0
+                    && (id as usize) < keys
+                    && length >= 0
+                    && !dictionary.words(id as usize).is_empty()
+            })
+        {
+            return Err(DICTIONARY_FILES[5]);
+        }
+        if dictionary.codes.len() != CODES * 8
+            || !(0..CODES).all(|code| {
+                let category = i32_at(&dictionary.codes, code);
+                category >= 0 && (category as usize) < count
+            })
+        {
+            return Err(DICTIONARY_FILES[6]);
+        }
+
+        let space: Vec<u16> = "SPACE".encode_utf16().collect();
+        dictionary.space = dictionary.trie().find(&space);
+        dictionary.features = (0..words).map(|_| OnceLock::new()).collect();
+        Ok(dictionary)
+    }
+
+    /// Returns the trie of all surfaces and categories
+    pub(crate) fn trie(&self) -> Trie<'_> {
+        Trie::new(&self.trie).expect("the trie is checked when loading")
+    }
+
+    /// Returns the number of words
+    pub(crate) fn len(&self) -> usize {
+        self.words
+    }
+
+    /// Returns the words stored under the key `id`
+    pub(crate) fn words(&self, id: usize) -> Range<usize> {
+        i32_at(&self.indices, id) as usize..i32_at(&self.indices, id + 1) as usize
+    }
+
+    pub(crate) fn left_id(&self, word: usize) -> i16 {
+        i16_at(&self.info, (self.words + 1) * 2 + word)
+    }
+
+    pub(crate) fn right_id(&self, word: usize) -> i16 {
+        i16_at(&self.info, (self.words + 1) * 3 + word)
+    }
+
+    pub(crate) fn cost(&self, word: usize) -> i16 {
+        i16_at(&self.info, (self.words + 1) * 4 + word)
+    }
+
+    /// Returns the comma separated features of `word`
+    pub(crate) fn feature(&self, word: usize) -> &str {
+        self.features[word].get_or_init(|| {
+            let units: Vec<u16> = self.feature_units(word).collect();
+            String::from_utf16_lossy(&units).into_boxed_str()
+        })
+    }
+
+    /// Returns the first `count` feature columns of `word` without decoding the rest of its
+    /// features
+    pub(crate) fn feature_prefix(&self, word: usize, count: usize) -> String {
+        let mut columns = 0;
+        let units = self.feature_units(word).take_while(|&unit| {
+            columns += (unit == u16::from(b',')) as usize;
+            columns < count
+        });
+        char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    }
+
+    fn feature_units(&self, word: usize) -> impl Iterator<Item = u16> + '_ {
+        let (start, end) = (i32_at(&self.info, word), i32_at(&self.info, word + 1));
+        (start as usize..end as usize).map(move |i| u16_at(&self.data, i))
+    }
+
+    /// Returns the cost of a word with the right id `right` followed by one with the left id
+    /// `left`
+    pub(crate) fn link_cost(&self, right: i16, left: i16) -> i32 {
+        i16_at(
+            &self.matrix,
+            4 + left as usize * self.right_ids + right as usize,
+        ) as i32
+    }
+
+    /// Returns the number of left ids and right ids defined by the matrix
+    pub(crate) fn context_ids(&self) -> (usize, usize) {
+        let left_ids = i32_at(&self.matrix, 1) as usize;
+        (left_ids, self.right_ids)
+    }
+
+    /// Returns the category of the code unit `code`
+    pub(crate) fn category(&self, code: u16) -> Category {
+        let index = i32_at(&self.codes, code as usize) as usize * 4;
+        let value = |i| i32_at(&self.categories, index + i);
+        Category {
+            id: value(0) as usize,
+            length: value(1) as usize,
+            invoke: value(2) != 0,
+            group: value(3) != 0,
+        }
+    }
+
+    /// Returns `true` if unknown text may continue from `first` to `code`, because they share a
+    /// category
+    pub(crate) fn compatible(&self, first: u16, code: u16) -> bool {
+        let mask = |code: u16| i32_at(&self.codes, CODES + code as usize);
+        mask(first) & mask(code) != 0
+    }
+
+    /// Returns `true` if the unknown words of `category` are spaces
+    pub(crate) fn is_space(&self, category: &Category) -> bool {
+        self.space == Some(category.id)
+    }
+}
+
+/// Returns `true` if `values` never decrease and lie within `0..=limit`
+fn is_ascending(values: impl Iterator<Item = i32>, limit: usize) -> bool {
+    let mut last = 0;
+    for value in values {
+        if value < last || value as usize > limit {
+            return false;
+        }
+        last = value;
+    }
+    true
+}
+
+/// Returns the directory of a small dictionary for tests, which is compiled once per process
+#[cfg(test)]
+pub(crate) fn fixture() -> &'static Path {
+    use std::path::PathBuf;
+
+    use crate::{compile_dictionary, CHAR_FILE, LEX_FILE, MATRIX_FILE, UNK_FILE};
+
+    const FEATURES: &str = ",*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*,*";
+    const LEX: &[&str] = &[
+        "東京,1,1,3000,名詞,固有名詞,地名,一般",
+        "東京都,1,1,2500,名詞,固有名詞,地名,一般",
+        "都,1,1,4000,名詞,普通名詞,一般,*",
+        "庁,1,1,4000,名詞,普通名詞,一般,*",
+        "に,2,2,100,助詞,格助詞,*,*",
+    ];
+    const UNK: &[&str] = &[
+        "DEFAULT,1,1,5000,補助記号,一般,*,*",
+        "SPACE,0,0,0,空白,*,*,*",
+        "KANJI,1,1,8000,名詞,普通名詞,一般,*",
+        "HIRAGANA,1,1,9000,名詞,普通名詞,一般,*",
+        "KATAKANA,1,1,7000,名詞,普通名詞,一般,*",
+    ];
+    // Right id 1 followed by left id 2 and the other way around
+    const MATRIX: &str = "3 3\n\
+        0 0 0\n0 1 0\n0 2 0\n1 0 0\n1 1 0\n1 2 100\n2 0 0\n2 1 200\n2 2 0\n";
+    const CHAR_DEF: &str = "DEFAULT 0 1 0\n\
+        SPACE 0 1 0\n\
+        KANJI 0 0 2\n\
+        HIRAGANA 0 1 0\n\
+        KATAKANA 1 1 0\n\
+        0x0020 SPACE\n\
+        0x3041..0x309F HIRAGANA\n\
+        0x30A1..0x30FF KATAKANA\n\
+        0x4E00..0x9FFF KANJI\n";
+
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = std::env::temp_dir().join(format!("igo-unidic-fixture-{}", std::process::id()));
+        let sources = dir.join("sources");
+        std::fs::create_dir_all(&sources).unwrap();
+
+        let words = |lines: &[&str]| -> String {
+            lines
+                .iter()
+                .map(|line| format!("{}{}\n", line, FEATURES))
+                .collect()
+        };
+        for (file, content) in [
+            (LEX_FILE, words(LEX)),
+            (UNK_FILE, words(UNK)),
+            (MATRIX_FILE, MATRIX.to_string()),
+            (CHAR_FILE, CHAR_DEF.to_string()),
+        ] {
+            std::fs::write(sources.join(file), content).unwrap();
+        }
+
+        compile_dictionary(&sources, &dir, |_| {}).unwrap();
+        dir
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_files() -> Vec<Vec<u8>> {
+        DICTIONARY_FILES
+            .iter()
+            .map(|name| std::fs::read(fixture().join(name)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn fixture_dictionary() {
+        let dictionary = Dictionary::load(fixture()).unwrap();
+        assert_eq!(dictionary.len(), 10);
+        assert_eq!(dictionary.context_ids(), (3, 3));
+        assert_eq!(dictionary.link_cost(1, 2), 100);
+        assert_eq!(dictionary.link_cost(2, 1), 200);
+
+        let key: Vec<u16> = "東京都庁".encode_utf16().collect();
+        let mut found = Vec::new();
+        dictionary.trie().common_prefix(&key, |len, id| {
+            found.extend(dictionary.words(id).map(|w| (len, w)))
+        });
+        assert_eq!(found.len(), 2);
+        let (len, word) = found[1];
+        assert_eq!(len, 3);
+        assert_eq!(
+            (
+                dictionary.left_id(word),
+                dictionary.right_id(word),
+                dictionary.cost(word)
+            ),
+            (1, 1, 2500)
+        );
+        assert!(dictionary
+            .feature(word)
+            .starts_with("名詞,固有名詞,地名,一般,*,"));
+        assert_eq!(dictionary.feature_prefix(word, 2), "名詞,固有名詞");
+
+        let kanji = dictionary.category('東' as u16);
+        assert_eq!((kanji.length, kanji.invoke, kanji.group), (2, false, false));
+        assert!(dictionary.is_space(&dictionary.category(' ' as u16)));
+        assert!(!dictionary.is_space(&kanji));
+        assert!(dictionary.compatible('ア' as u16, 'ー' as u16));
+        assert!(!dictionary.compatible('ア' as u16, '東' as u16));
+    }
+
+    #[test]
+    fn inconsistent_files_are_rejected() {
+        let load = |name: &str, file: usize, bytes: &[u8]| {
+            let dir = std::env::temp_dir().join(format!(
+                "igo-unidic-broken-{}-{}",
+                name,
+                std::process::id()
+            ));
+            std::fs::create_dir_all(&dir).unwrap();
+            for (i, (name, fixture)) in DICTIONARY_FILES.iter().zip(fixture_files()).enumerate() {
+                let content = if i == file { bytes } else { &fixture };
+                std::fs::write(dir.join(name), content).unwrap();
+            }
+            let result = Dictionary::load(&dir).err();
+            std::fs::remove_dir_all(&dir).unwrap();
+            match result {
+                Some(DictionaryError::Corrupt(file)) => file.file_name().map(|n| n.to_owned()),
+                _ => None,
+            }
+        };
+
+        let matrix = [3, 0, 0, 0, 3, 0, 0, 0];
+        assert_eq!(load("matrix", 4, &matrix).unwrap(), "matrix.bin");
+
+        // A word with a left id outside of the matrix
+        let mut info = fixture_files().swap_remove(3);
+        let left_ids = info.len() / 10 * 4;
+        info[left_ids] = 3;
+        assert_eq!(load("ids", 3, &info).unwrap(), "word.inf");
+
+        let trie = fixture_files().swap_remove(0);
+        assert_eq!(load("trie", 0, &trie[1..]).unwrap(), "word2id");
+    }
+}
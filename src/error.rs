@@ -0,0 +1,344 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+
+use crate::FeatureColumn;
+
+/// Error returned when converting raw feature columns into a typed value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FeatureError {
+    /// The column contained a value without a typed mapping
+    UnknownValue {
+        column: FeatureColumn,
+        value: String,
+    },
+    /// The feature string ended before the column
+    MissingColumn { column: FeatureColumn },
+}
+
+impl FeatureError {
+    pub(crate) fn unknown(column: FeatureColumn, value: &str) -> Self {
+        Self::UnknownValue {
+            column,
+            value: value.to_string(),
+        }
+    }
+
+    /// Returns the column which caused the error
+    pub fn column(&self) -> FeatureColumn {
+        match self {
+            Self::UnknownValue { column, .. } | Self::MissingColumn { column } => *column,
+        }
+    }
+
+    /// Attaches the position of the morpheme the feature belongs to
+    pub fn at(self, surface: &str, start: usize) -> ParseError {
+        let surface = surface.to_string();
+        match self {
+            Self::UnknownValue { column, value } => ParseError::UnknownValue {
+                column,
+                value,
+                surface,
+                start,
+            },
+            Self::MissingColumn { column } => ParseError::MissingColumn {
+                column,
+                surface,
+                start,
+            },
+        }
+    }
+}
+
+impl fmt::Display for FeatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownValue { column, value } => {
+                write!(f, "unexpected value {:?} in {}", value, column)
+            }
+            Self::MissingColumn { column } => write!(f, "missing {}", column),
+        }
+    }
+}
+
+impl Error for FeatureError {}
+
+/// Error returned by [`Parser::try_parse`](crate::Parser::try_parse)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A feature column contained a value without a typed mapping
+    UnknownValue {
+        column: FeatureColumn,
+        value: String,
+        surface: String,
+        start: usize,
+    },
+    /// The feature string of a morpheme ended before a required column
+    MissingColumn {
+        column: FeatureColumn,
+        surface: String,
+        start: usize,
+    },
+}
+
+impl ParseError {
+    /// Returns the column which caused the error
+    pub fn column(&self) -> FeatureColumn {
+        match self {
+            Self::UnknownValue { column, .. } | Self::MissingColumn { column, .. } => *column,
+        }
+    }
+
+    /// Returns the surface of the morpheme which failed to parse
+    pub fn surface(&self) -> &str {
+        match self {
+            Self::UnknownValue { surface, .. } | Self::MissingColumn { surface, .. } => surface,
+        }
+    }
+
+    /// Returns the byte offset of the morpheme which failed to parse
+    pub fn start(&self) -> usize {
+        match self {
+            Self::UnknownValue { start, .. } | Self::MissingColumn { start, .. } => *start,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownValue {
+                column,
+                value,
+                surface,
+                start,
+            } => write!(
+                f,
+                "unexpected value {:?} in {} of {:?} at {}",
+                value, column, surface, start
+            ),
+            Self::MissingColumn {
+                column,
+                surface,
+                start,
+            } => write!(f, "missing {} of {:?} at {}", column, surface, start),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Error returned when loading the dictionary of a [`Parser`](crate::Parser)
+#[derive(Debug)]
+pub enum DictionaryError {
+    /// The dictionary path is not a directory
+    NotADirectory(PathBuf),
+    /// A required dictionary file does not exist
+    MissingFile(PathBuf),
+    /// A dictionary file exists but is empty
+    Corrupt(PathBuf),
+    /// The contents given for the dictionary file of this name are inconsistent
+    CorruptBytes(&'static str),
+    /// A dictionary file could not be read
+    Io { file: PathBuf, source: io::Error },
+    /// The dictionary files were present but could not be loaded
+    Load { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotADirectory(path) => write!(f, "{} is not a directory", path.display()),
+            Self::MissingFile(file) => write!(f, "dictionary file {} is missing", file.display()),
+            Self::Corrupt(file) => write!(f, "dictionary file {} is corrupt", file.display()),
+            Self::CorruptBytes(name) => {
+                write!(f, "contents of dictionary file {} are corrupt", name)
+            }
+            Self::Io { file, source } => write!(f, "can't read {}: {}", file.display(), source),
+            Self::Load { path, source } => {
+                write!(f, "can't load dictionary {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl Error for DictionaryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } | Self::Load { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned when loading a [`UserDictionary`](crate::UserDictionary)
+#[derive(Debug)]
+pub enum UserDictionaryError {
+    /// The dictionary could not be read
+    Io(io::Error),
+    /// A line has neither the simplified nor the UniDic column layout
+    Columns { line: usize, count: usize },
+    /// The cost column of a line is not a number
+    Cost { line: usize, value: String },
+    /// A context id column of a line is not a number
+    ContextId { line: usize, value: String },
+    /// A feature of an entry has no typed mapping
+    Feature { line: usize, source: FeatureError },
+    /// A context id of an entry is not defined by the matrix of the system dictionary
+    UnknownContextId {
+        surface: String,
+        id: i16,
+        limit: usize,
+    },
+    /// The system dictionary has no word of the word class of an entry, so the entry has no
+    /// context ids, or the word class it has there can't be mapped
+    WordClass { surface: String, word_class: String },
+}
+
+impl fmt::Display for UserDictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(source) => write!(f, "can't read user dictionary: {}", source),
+            Self::Columns { line, count } => {
+                write!(f, "line {}: unexpected number of columns {}", line, count)
+            }
+            Self::Cost { line, value } => write!(f, "line {}: invalid cost {:?}", line, value),
+            Self::ContextId { line, value } => {
+                write!(f, "line {}: invalid context id {:?}", line, value)
+            }
+            Self::Feature { line, source } => write!(f, "line {}: {}", line, source),
+            Self::UnknownContextId { surface, id, limit } => write!(
+                f,
+                "{}: context id {} is not below {}, the number of ids of the system dictionary",
+                surface, id, limit
+            ),
+            Self::WordClass {
+                surface,
+                word_class,
+            } => write!(
+                f,
+                "{}: the system dictionary has no usable word of the word class {}",
+                surface, word_class
+            ),
+        }
+    }
+}
+
+impl Error for UserDictionaryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(source) => Some(source),
+            Self::Feature { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned by [`compile_dictionary`](crate::compile_dictionary)
+#[derive(Debug)]
+pub enum CompileError {
+    /// A source file could not be read or a dictionary file could not be written
+    Io { file: PathBuf, source: io::Error },
+    /// A line has too few columns
+    Columns {
+        file: PathBuf,
+        line: usize,
+        count: usize,
+    },
+    /// A column is not a number or doesn't fit its type
+    Number {
+        file: PathBuf,
+        line: usize,
+        value: String,
+    },
+    /// A context id is not defined by `matrix.def`
+    ContextId {
+        file: PathBuf,
+        line: usize,
+        id: i16,
+        limit: usize,
+    },
+    /// A surface is empty, too long or contains U+0000
+    Surface {
+        file: PathBuf,
+        line: usize,
+        value: String,
+    },
+    /// A line refers to a category which is not defined in `char.def`
+    UnknownCategory {
+        file: PathBuf,
+        line: usize,
+        name: String,
+    },
+    /// A category is not defined in `char.def` or has no words in `unk.def`
+    MissingCategory(String),
+    /// `char.def` defines more than 32 categories
+    TooManyCategories(usize),
+    /// `matrix.def` doesn't define the cost of every pair of context ids
+    IncompleteMatrix { expected: usize, found: usize },
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { file, source } => write!(f, "{}: {}", file.display(), source),
+            Self::Columns { file, line, count } => write!(
+                f,
+                "{}:{}: unexpected number of columns {}",
+                file.display(),
+                line,
+                count
+            ),
+            Self::Number { file, line, value } => {
+                write!(f, "{}:{}: invalid number {:?}", file.display(), line, value)
+            }
+            Self::ContextId {
+                file,
+                line,
+                id,
+                limit,
+            } => write!(
+                f,
+                "{}:{}: context id {} is not below {}",
+                file.display(),
+                line,
+                id,
+                limit
+            ),
+            Self::Surface { file, line, value } => {
+                write!(
+                    f,
+                    "{}:{}: invalid surface {:?}",
+                    file.display(),
+                    line,
+                    value
+                )
+            }
+            Self::UnknownCategory { file, line, name } => {
+                write!(f, "{}:{}: unknown category {}", file.display(), line, name)
+            }
+            Self::MissingCategory(name) => write!(f, "category {} is missing", name),
+            Self::TooManyCategories(count) => {
+                write!(f, "{} categories defined, at most 32 are supported", count)
+            }
+            Self::IncompleteMatrix { expected, found } => write!(
+                f,
+                "matrix defines {} of {} connection costs",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl Error for CompileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for UserDictionaryError {
+    fn from(source: io::Error) -> Self {
+        Self::Io(source)
+    }
+}
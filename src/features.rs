@@ -0,0 +1,355 @@
+use std::fmt;
+
+use crate::str_or_empty;
+
+/// A column of the UniDic feature string
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FeatureColumn {
+    /// Part of speech (`pos1`)
+    Pos1,
+    /// Part of speech, first subcategory (`pos2`)
+    Pos2,
+    /// Part of speech, second subcategory (`pos3`)
+    Pos3,
+    /// Part of speech, third subcategory (`pos4`)
+    Pos4,
+    /// Conjungation type (`cType`)
+    CType,
+    /// Conjungation form (`cForm`)
+    CForm,
+    /// Reading of the lemma in katakana (`lForm`)
+    LForm,
+    /// Lemma (`lemma`)
+    Lemma,
+    /// Orthography of the surface (`orth`)
+    Orth,
+    /// Pronunciation of the surface (`pron`)
+    Pron,
+    /// Orthography of the dictionary form (`orthBase`)
+    OrthBase,
+    /// Pronunciation of the dictionary form (`pronBase`)
+    PronBase,
+    /// Word origin (`goshu`)
+    Goshu,
+    /// Type of the sound change at the beginning of the word (`iType`)
+    IType,
+    /// Form of the sound change at the beginning of the word (`iForm`)
+    IForm,
+    /// Type of the sound change at the end of the word (`fType`)
+    FType,
+    /// Form of the sound change at the end of the word (`fForm`)
+    FForm,
+    /// Combination type of the sound change at the beginning of the word (`iConType`)
+    IConType,
+    /// Combination type of the sound change at the end of the word (`fConType`)
+    FConType,
+    /// Lexeme type (`type`)
+    Type,
+    /// Reading of the surface in katakana (`kana`)
+    Kana,
+    /// Reading of the dictionary form in katakana (`kanaBase`)
+    KanaBase,
+    /// Form of the surface (`form`)
+    Form,
+    /// Dictionary form (`formBase`)
+    FormBase,
+    /// Accent type (`aType`)
+    AType,
+    /// Accent combination type (`aConType`)
+    AConType,
+    /// Accent modification type (`aModType`)
+    AModType,
+    /// Lexeme ID (`lid`)
+    Lid,
+    /// Lemma ID (`lemma_id`)
+    LemmaId,
+}
+
+impl FeatureColumn {
+    /// All columns, ordered by their position in the feature string
+    pub const ALL: [FeatureColumn; 29] = [
+        Self::Pos1,
+        Self::Pos2,
+        Self::Pos3,
+        Self::Pos4,
+        Self::CType,
+        Self::CForm,
+        Self::LForm,
+        Self::Lemma,
+        Self::Orth,
+        Self::Pron,
+        Self::OrthBase,
+        Self::PronBase,
+        Self::Goshu,
+        Self::IType,
+        Self::IForm,
+        Self::FType,
+        Self::FForm,
+        Self::IConType,
+        Self::FConType,
+        Self::Type,
+        Self::Kana,
+        Self::KanaBase,
+        Self::Form,
+        Self::FormBase,
+        Self::AType,
+        Self::AConType,
+        Self::AModType,
+        Self::Lid,
+        Self::LemmaId,
+    ];
+
+    /// Returns the position of the column within the comma separated feature string
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Pos1 => 0,
+            Self::Pos2 => 1,
+            Self::Pos3 => 2,
+            Self::Pos4 => 3,
+            Self::CType => 4,
+            Self::CForm => 5,
+            Self::LForm => 6,
+            Self::Lemma => 7,
+            Self::Orth => 8,
+            Self::Pron => 9,
+            Self::OrthBase => 10,
+            Self::PronBase => 11,
+            Self::Goshu => 12,
+            Self::IType => 13,
+            Self::IForm => 14,
+            Self::FType => 15,
+            Self::FForm => 16,
+            Self::IConType => 17,
+            Self::FConType => 18,
+            Self::Type => 19,
+            Self::Kana => 20,
+            Self::KanaBase => 21,
+            Self::Form => 22,
+            Self::FormBase => 23,
+            Self::AType => 24,
+            Self::AConType => 25,
+            Self::AModType => 26,
+            Self::Lid => 27,
+            Self::LemmaId => 28,
+        }
+    }
+
+    /// Returns the name UniDic uses for the column
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pos1 => "pos1",
+            Self::Pos2 => "pos2",
+            Self::Pos3 => "pos3",
+            Self::Pos4 => "pos4",
+            Self::CType => "cType",
+            Self::CForm => "cForm",
+            Self::LForm => "lForm",
+            Self::Lemma => "lemma",
+            Self::Orth => "orth",
+            Self::Pron => "pron",
+            Self::OrthBase => "orthBase",
+            Self::PronBase => "pronBase",
+            Self::Goshu => "goshu",
+            Self::IType => "iType",
+            Self::IForm => "iForm",
+            Self::FType => "fType",
+            Self::FForm => "fForm",
+            Self::IConType => "iConType",
+            Self::FConType => "fConType",
+            Self::Type => "type",
+            Self::Kana => "kana",
+            Self::KanaBase => "kanaBase",
+            Self::Form => "form",
+            Self::FormBase => "formBase",
+            Self::AType => "aType",
+            Self::AConType => "aConType",
+            Self::AModType => "aModType",
+            Self::Lid => "lid",
+            Self::LemmaId => "lemma_id",
+        }
+    }
+}
+
+impl fmt::Display for FeatureColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (column {})", self.name(), self.index())
+    }
+}
+
+/// All columns of a UniDic feature string. Columns missing in the dictionary are empty
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnidicFeatures<'dict> {
+    /// Part of speech (`pos1`)
+    pub pos1: &'dict str,
+    /// Part of speech, first subcategory (`pos2`)
+    pub pos2: &'dict str,
+    /// Part of speech, second subcategory (`pos3`)
+    pub pos3: &'dict str,
+    /// Part of speech, third subcategory (`pos4`)
+    pub pos4: &'dict str,
+    /// Conjungation type (`cType`)
+    pub c_type: &'dict str,
+    /// Conjungation form (`cForm`)
+    pub c_form: &'dict str,
+    /// Reading of the lemma in katakana (`lForm`)
+    pub l_form: &'dict str,
+    /// Lemma (`lemma`)
+    pub lemma: &'dict str,
+    /// Orthography of the surface (`orth`)
+    pub orth: &'dict str,
+    /// Pronunciation of the surface (`pron`)
+    pub pron: &'dict str,
+    /// Orthography of the dictionary form (`orthBase`)
+    pub orth_base: &'dict str,
+    /// Pronunciation of the dictionary form (`pronBase`)
+    pub pron_base: &'dict str,
+    /// Word origin (`goshu`)
+    pub goshu: &'dict str,
+    /// Type of the sound change at the beginning of the word (`iType`)
+    pub i_type: &'dict str,
+    /// Form of the sound change at the beginning of the word (`iForm`)
+    pub i_form: &'dict str,
+    /// Type of the sound change at the end of the word (`fType`)
+    pub f_type: &'dict str,
+    /// Form of the sound change at the end of the word (`fForm`)
+    pub f_form: &'dict str,
+    /// Combination type of the sound change at the beginning of the word (`iConType`)
+    pub i_con_type: &'dict str,
+    /// Combination type of the sound change at the end of the word (`fConType`)
+    pub f_con_type: &'dict str,
+    /// Lexeme type (`type`)
+    pub r#type: &'dict str,
+    /// Reading of the surface in katakana (`kana`)
+    pub kana: &'dict str,
+    /// Reading of the dictionary form in katakana (`kanaBase`)
+    pub kana_base: &'dict str,
+    /// Form of the surface (`form`)
+    pub form: &'dict str,
+    /// Dictionary form (`formBase`)
+    pub form_base: &'dict str,
+    /// Accent type (`aType`)
+    pub a_type: &'dict str,
+    /// Accent combination type (`aConType`)
+    pub a_con_type: &'dict str,
+    /// Accent modification type (`aModType`)
+    pub a_mod_type: &'dict str,
+    /// Lexeme ID (`lid`)
+    pub lid: &'dict str,
+    /// Lemma ID (`lemma_id`)
+    pub lemma_id: &'dict str,
+}
+
+impl<'dict> UnidicFeatures<'dict> {
+    /// Splits a raw, comma separated feature string into its columns
+    pub fn from_feature(feature: &'dict str) -> Self {
+        let features = split_features(feature);
+        Self::from_columns(&features)
+    }
+
+    /// Builds the features from already split columns
+    pub fn from_columns(features: &[&'dict str]) -> Self {
+        let get = |column: FeatureColumn| str_or_empty(features, column.index());
+        UnidicFeatures {
+            pos1: get(FeatureColumn::Pos1),
+            pos2: get(FeatureColumn::Pos2),
+            pos3: get(FeatureColumn::Pos3),
+            pos4: get(FeatureColumn::Pos4),
+            c_type: get(FeatureColumn::CType),
+            c_form: get(FeatureColumn::CForm),
+            l_form: get(FeatureColumn::LForm),
+            lemma: get(FeatureColumn::Lemma),
+            orth: get(FeatureColumn::Orth),
+            pron: get(FeatureColumn::Pron),
+            orth_base: get(FeatureColumn::OrthBase),
+            pron_base: get(FeatureColumn::PronBase),
+            goshu: get(FeatureColumn::Goshu),
+            i_type: get(FeatureColumn::IType),
+            i_form: get(FeatureColumn::IForm),
+            f_type: get(FeatureColumn::FType),
+            f_form: get(FeatureColumn::FForm),
+            i_con_type: get(FeatureColumn::IConType),
+            f_con_type: get(FeatureColumn::FConType),
+            r#type: get(FeatureColumn::Type),
+            kana: get(FeatureColumn::Kana),
+            kana_base: get(FeatureColumn::KanaBase),
+            form: get(FeatureColumn::Form),
+            form_base: get(FeatureColumn::FormBase),
+            a_type: get(FeatureColumn::AType),
+            a_con_type: get(FeatureColumn::AConType),
+            a_mod_type: get(FeatureColumn::AModType),
+            lid: get(FeatureColumn::Lid),
+            lemma_id: get(FeatureColumn::LemmaId),
+        }
+    }
+
+    /// Returns the value of `column`
+    pub fn get(&self, column: FeatureColumn) -> &'dict str {
+        match column {
+            FeatureColumn::Pos1 => self.pos1,
+            FeatureColumn::Pos2 => self.pos2,
+            FeatureColumn::Pos3 => self.pos3,
+            FeatureColumn::Pos4 => self.pos4,
+            FeatureColumn::CType => self.c_type,
+            FeatureColumn::CForm => self.c_form,
+            FeatureColumn::LForm => self.l_form,
+            FeatureColumn::Lemma => self.lemma,
+            FeatureColumn::Orth => self.orth,
+            FeatureColumn::Pron => self.pron,
+            FeatureColumn::OrthBase => self.orth_base,
+            FeatureColumn::PronBase => self.pron_base,
+            FeatureColumn::Goshu => self.goshu,
+            FeatureColumn::IType => self.i_type,
+            FeatureColumn::IForm => self.i_form,
+            FeatureColumn::FType => self.f_type,
+            FeatureColumn::FForm => self.f_form,
+            FeatureColumn::IConType => self.i_con_type,
+            FeatureColumn::FConType => self.f_con_type,
+            FeatureColumn::Type => self.r#type,
+            FeatureColumn::Kana => self.kana,
+            FeatureColumn::KanaBase => self.kana_base,
+            FeatureColumn::Form => self.form,
+            FeatureColumn::FormBase => self.form_base,
+            FeatureColumn::AType => self.a_type,
+            FeatureColumn::AConType => self.a_con_type,
+            FeatureColumn::AModType => self.a_mod_type,
+            FeatureColumn::Lid => self.lid,
+            FeatureColumn::LemmaId => self.lemma_id,
+        }
+    }
+}
+
+/// Splits a raw feature string into its columns. Columns holding several values, like an aType
+/// of `"0,2"`, are quoted and returned without their quotes
+pub(crate) fn split_features(feature: &str) -> Vec<&str> {
+    let mut columns = Vec::new();
+    let mut rest = feature;
+
+    loop {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            if let Some(end) = quoted
+                .find("\",")
+                .or_else(|| quoted.strip_suffix('"').map(str::len))
+            {
+                columns.push(&quoted[..end]);
+                match quoted[end + 1..].strip_prefix(',') {
+                    Some(next) => {
+                        rest = next;
+                        continue;
+                    }
+                    None => return columns,
+                }
+            }
+        }
+
+        match rest.split_once(',') {
+            Some((column, next)) => {
+                columns.push(column);
+                rest = next;
+            }
+            None => {
+                columns.push(rest);
+                return columns;
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    kana::{hiragana_to_katakana_char, is_kana, is_kanji},
+    Morpheme,
+};
+
+impl<'dict, 'input> Morpheme<'dict, 'input> {
+    /// Splits the surface into segments and assigns each non kana segment its part of the
+    /// katakana reading. See [`furigana`]
+    pub fn furigana(&self) -> Vec<(&'input str, Option<&'dict str>)> {
+        furigana(self.surface, self.kana_reading())
+    }
+
+    /// Returns the katakana reading of the surface as it would be written
+    pub(crate) fn kana_reading(&self) -> &'dict str {
+        // `kana` holds the reading as written, `reading` the pronunciation, which uses `ー` for
+        // long vowels
+        let kana = self.features().kana;
+        let reading = if kana.is_empty() || kana == "*" {
+            self.reading
+        } else {
+            kana
+        };
+
+        if reading == "*" {
+            ""
+        } else {
+            reading
+        }
+    }
+}
+
+/// Splits `surface` into segments and assigns each segment which isn't written in kana its part
+/// of `reading`. Kana segments, like okurigana, don't get a reading.
+///
+/// `食べた` with the reading `タベタ` results in `[("食", Some("タ")), ("べた", None)]`.
+///
+/// If the reading can't be aligned, the whole surface gets the whole reading.
+pub fn furigana<'s, 'r>(surface: &'s str, reading: &'r str) -> Vec<(&'s str, Option<&'r str>)> {
+    let segments = segments(surface);
+    if reading.is_empty() || segments.iter().all(|(needs_reading, _)| !needs_reading) {
+        return vec![(surface, None)];
+    }
+
+    let reading_chars: Vec<_> = reading.chars().collect();
+    let mut lengths = Vec::with_capacity(segments.len());
+    if !align(&segments, 0, &reading_chars, 0, &mut lengths) {
+        return vec![(surface, Some(reading))];
+    }
+
+    // Byte offset of every char of the reading, plus its end
+    let offsets: Vec<_> = reading
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(reading.len()))
+        .collect();
+
+    let mut pos = 0;
+    segments
+        .into_iter()
+        .zip(lengths)
+        .map(|((needs_reading, text), len)| {
+            let part = &reading[offsets[pos]..offsets[pos + len]];
+            pos += len;
+
+            if needs_reading && part != text {
+                (text, Some(part))
+            } else {
+                (text, None)
+            }
+        })
+        .collect()
+}
+
+/// Splits `surface` into runs of kana and runs of characters which need a reading. A ヶ or ケ
+/// between kanji, like in 一ヶ月, is a counter and read together with them
+fn segments(surface: &str) -> Vec<(bool, &str)> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut current = None;
+    let mut prev = None;
+    let mut chars = surface.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|(_, next)| *next);
+        let counter = matches!(c, 'ヶ' | 'ケ' | 'ヵ')
+            && prev.is_some_and(is_kanji)
+            && next.is_some_and(is_kanji);
+        prev = Some(c);
+
+        let needs_reading = !is_kana(c) || counter;
+        if current.is_some() && current != Some(needs_reading) {
+            segments.push((!needs_reading, &surface[start..i]));
+            start = i;
+        }
+        current = Some(needs_reading);
+    }
+
+    if let Some(needs_reading) = current {
+        segments.push((needs_reading, &surface[start..]));
+    }
+
+    segments
+}
+
+/// Assigns each segment starting at `seg` a part of the reading starting at `pos`. Kana segments
+/// have to match the reading, all other segments take at least one character of it. The amount
+/// of reading characters of each segment is pushed to `lengths`
+fn align(
+    segments: &[(bool, &str)],
+    seg: usize,
+    reading: &[char],
+    pos: usize,
+    lengths: &mut Vec<usize>,
+) -> bool {
+    let (needs_reading, text) = match segments.get(seg) {
+        Some(segment) => *segment,
+        None => return pos == reading.len(),
+    };
+
+    let remaining = reading.len() - pos;
+
+    let candidates = if needs_reading {
+        1..remaining + 1
+    } else {
+        let len = text.chars().count();
+        let matches = len <= remaining
+            && text
+                .chars()
+                .zip(&reading[pos..])
+                .all(|(a, b)| hiragana_to_katakana_char(a) == hiragana_to_katakana_char(*b));
+        if !matches {
+            return false;
+        }
+        len..len + 1
+    };
+
+    for len in candidates {
+        lengths.push(len);
+        if align(segments, seg + 1, reading, pos + len, lengths) {
+            return true;
+        }
+        lengths.pop();
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn okurigana_get_no_reading() {
+        assert_eq!(
+            furigana("食べた", "タベタ"),
+            vec![("食", Some("タ")), ("べた", None)]
+        );
+        assert_eq!(
+            furigana("取り扱い", "トリアツカイ"),
+            vec![
+                ("取", Some("ト")),
+                ("り", None),
+                ("扱", Some("アツカ")),
+                ("い", None)
+            ]
+        );
+        assert_eq!(
+            furigana("お茶", "オチャ"),
+            vec![("お", None), ("茶", Some("チャ"))]
+        );
+    }
+
+    #[test]
+    fn kana_only_surfaces_get_no_reading() {
+        assert_eq!(furigana("たべる", "タベル"), vec![("たべる", None)]);
+        assert_eq!(furigana("東京", ""), vec![("東京", None)]);
+    }
+
+    #[test]
+    fn counter_ke_is_part_of_the_kanji() {
+        assert_eq!(
+            furigana("一ヶ月", "イッカゲツ"),
+            vec![("一ヶ月", Some("イッカゲツ"))]
+        );
+        assert_eq!(
+            furigana("三ケ日は", "ミッカビハ"),
+            vec![("三ケ日", Some("ミッカビ")), ("は", None)]
+        );
+        assert_eq!(
+            furigana("ケ月", "ケゲツ"),
+            vec![("ケ", None), ("月", Some("ゲツ"))]
+        );
+    }
+
+    #[test]
+    fn unaligned_readings_cover_the_whole_surface() {
+        assert_eq!(
+            furigana("食べた", "ノンダ"),
+            vec![("食べた", Some("ノンダ"))]
+        );
+    }
+}
@@ -0,0 +1,583 @@
+use crate::{kana::is_kana, AdjectiveType, SyllableRow, VerbType};
+
+/// A form a verb or adjective can be conjungated into
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Inflection {
+    /// 食べる, 食べます
+    Dictionary,
+    /// 食べない, 食べません
+    Negative,
+    /// 食べて, 食べまして
+    Te,
+    /// 食べた, 食べました
+    Past,
+    /// 食べれば. The polite register uses 食べましたら, as 食べますれば is hardly used
+    Conditional,
+    /// 食べよう, 食べましょう
+    Volitional,
+    /// 食べられる, 食べられます
+    Potential,
+    /// 食べられる, 食べられます
+    Passive,
+    /// 食べさせる, 食べさせます
+    Causative,
+    /// 食べさせられる, 食べさせられます
+    CausativePassive,
+    /// 食べろ, 食べてください
+    Imperative,
+}
+
+impl Inflection {
+    /// All inflections, in the order they are declared
+    pub const ALL: [Inflection; 11] = [
+        Self::Dictionary,
+        Self::Negative,
+        Self::Te,
+        Self::Past,
+        Self::Conditional,
+        Self::Volitional,
+        Self::Potential,
+        Self::Passive,
+        Self::Causative,
+        Self::CausativePassive,
+        Self::Imperative,
+    ];
+}
+
+/// Politeness of a conjungated form
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Register {
+    Plain,
+    Polite,
+}
+
+/// Godan verbs using い instead of り for their 連用形 and imperative
+const GODAN_I_ENDING: &[&str] = &[
+    "いらっしゃる",
+    "おっしゃる",
+    "仰る",
+    "くださる",
+    "下さる",
+    "なさる",
+    "為さる",
+    "ござる",
+    "御座る",
+];
+
+/// Godan verbs of the ワ行 using うて and うた instead of って and った
+const GODAN_U_TE: &[&str] = &["問う", "とう", "請う", "乞う", "恋う", "こう"];
+
+/// Endings of adjectives ending in いい which are not compounds of いい, like 小かわいい
+const NOT_II_COMPOUNDS: &[&str] = &["かわいい", "可愛いい", "かいい"];
+
+/// Stems and plain forms of a verb, from which all inflections are built
+struct VerbForms {
+    /// 未然形, used for ない
+    negative: String,
+    /// 連用形, used for ます
+    continuous: String,
+    te: String,
+    past: String,
+    conditional: String,
+    volitional: String,
+    imperative: String,
+    potential: String,
+    passive: String,
+    causative: String,
+    causative_passive: String,
+}
+
+impl<'a> VerbType<'a> {
+    /// Conjungates `lemma`, the dictionary form of a verb of this type. Returns `None` if the
+    /// verb type can't be conjungated or `lemma` doesn't fit the verb type
+    pub fn conjungate(
+        &self,
+        lemma: &str,
+        inflection: Inflection,
+        register: Register,
+    ) -> Option<String> {
+        let forms = self.forms(lemma)?;
+        let polite = register == Register::Polite;
+
+        Some(match inflection {
+            Inflection::Dictionary if polite => forms.continuous + "ます",
+            Inflection::Dictionary => lemma.to_string(),
+            Inflection::Negative if polite => forms.continuous + "ません",
+            Inflection::Negative if lemma == "ある" || lemma == "有る" => "ない".to_string(),
+            Inflection::Negative => forms.negative + "ない",
+            Inflection::Te if polite => forms.continuous + "まして",
+            Inflection::Te => forms.te,
+            Inflection::Past if polite => forms.continuous + "ました",
+            Inflection::Past => forms.past,
+            Inflection::Conditional if polite => forms.continuous + "ましたら",
+            Inflection::Conditional => forms.conditional,
+            Inflection::Volitional if polite => forms.continuous + "ましょう",
+            Inflection::Volitional => forms.volitional,
+            Inflection::Imperative if polite => forms.te + "ください",
+            Inflection::Imperative => forms.imperative,
+            Inflection::Potential => ichidan_register(forms.potential, register),
+            Inflection::Passive => ichidan_register(forms.passive, register),
+            Inflection::Causative => ichidan_register(forms.causative, register),
+            Inflection::CausativePassive => ichidan_register(forms.causative_passive, register),
+        })
+    }
+
+    fn forms(&self, lemma: &str) -> Option<VerbForms> {
+        match self {
+            Self::Godan(row) => godan_forms(lemma, *row),
+            Self::IrregRu => godan_forms(lemma, SyllableRow::R),
+            Self::IrregNu => godan_forms(lemma, SyllableRow::N),
+            Self::Ichidan(_) => {
+                let base = lemma.strip_suffix('る')?;
+                Some(VerbForms {
+                    negative: base.to_string(),
+                    continuous: base.to_string(),
+                    te: format!("{}て", base),
+                    past: format!("{}た", base),
+                    conditional: format!("{}れば", base),
+                    volitional: format!("{}よう", base),
+                    imperative: format!("{}ろ", base),
+                    potential: format!("{}られる", base),
+                    passive: format!("{}られる", base),
+                    causative: format!("{}させる", base),
+                    causative_passive: format!("{}させられる", base),
+                })
+            }
+            Self::Suru => {
+                let base = lemma.strip_suffix("する")?;
+                // Verbs of a single kanji like 愛する partly conjungate like godan verbs of the サ行
+                let single = base.chars().count() == 1 && !base.chars().all(is_kana);
+                let (negative, volitional, imperative, potential) = if single {
+                    ("さ", "そう", "せよ", "せる")
+                } else {
+                    ("し", "しよう", "しろ", "できる")
+                };
+                Some(VerbForms {
+                    negative: format!("{}{}", base, negative),
+                    continuous: format!("{}し", base),
+                    te: format!("{}して", base),
+                    past: format!("{}した", base),
+                    conditional: format!("{}すれば", base),
+                    volitional: format!("{}{}", base, volitional),
+                    imperative: format!("{}{}", base, imperative),
+                    potential: format!("{}{}", base, potential),
+                    passive: format!("{}される", base),
+                    causative: format!("{}させる", base),
+                    causative_passive: format!("{}させられる", base),
+                })
+            }
+            Self::Zuru => {
+                let base = lemma.strip_suffix("ずる")?;
+                Some(VerbForms {
+                    negative: format!("{}じ", base),
+                    continuous: format!("{}じ", base),
+                    te: format!("{}じて", base),
+                    past: format!("{}じた", base),
+                    conditional: format!("{}ずれば", base),
+                    volitional: format!("{}じよう", base),
+                    imperative: format!("{}じろ", base),
+                    potential: format!("{}じられる", base),
+                    passive: format!("{}じられる", base),
+                    causative: format!("{}じさせる", base),
+                    causative_passive: format!("{}じさせられる", base),
+                })
+            }
+            Self::Kuru => {
+                // Written with a kanji all stems are 来, in kana they are こ, き and く
+                let (base, ko, ki, ku) = match lemma.strip_suffix("来る") {
+                    Some(base) => (base, "来", "来", "来"),
+                    None => (lemma.strip_suffix("くる")?, "こ", "き", "く"),
+                };
+                Some(VerbForms {
+                    negative: format!("{}{}", base, ko),
+                    continuous: format!("{}{}", base, ki),
+                    te: format!("{}{}て", base, ki),
+                    past: format!("{}{}た", base, ki),
+                    conditional: format!("{}{}れば", base, ku),
+                    volitional: format!("{}{}よう", base, ko),
+                    imperative: format!("{}{}い", base, ko),
+                    potential: format!("{}{}られる", base, ko),
+                    passive: format!("{}{}られる", base, ko),
+                    causative: format!("{}{}させる", base, ko),
+                    causative_passive: format!("{}{}させられる", base, ko),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+fn godan_forms(lemma: &str, row: SyllableRow) -> Option<VerbForms> {
+    let [a, i, u, e, o] = row_kana(row)?;
+    let base = lemma.strip_suffix(u)?;
+
+    let (te, past) = match row {
+        _ if is_iku(lemma) => ("って", "った"),
+        SyllableRow::Wa if GODAN_U_TE.contains(&lemma) => ("うて", "うた"),
+        SyllableRow::K => ("いて", "いた"),
+        SyllableRow::G => ("いで", "いだ"),
+        SyllableRow::S => ("して", "した"),
+        SyllableRow::T | SyllableRow::R | SyllableRow::Wa => ("って", "った"),
+        _ => ("んで", "んだ"),
+    };
+
+    // いらっしゃる becomes いらっしゃいます and いらっしゃい
+    let (i, imperative) = if GODAN_I_ENDING.contains(&lemma) {
+        ('い', 'い')
+    } else {
+        (i, e)
+    };
+
+    Some(VerbForms {
+        negative: format!("{}{}", base, a),
+        continuous: format!("{}{}", base, i),
+        te: format!("{}{}", base, te),
+        past: format!("{}{}", base, past),
+        conditional: format!("{}{}ば", base, e),
+        volitional: format!("{}{}う", base, o),
+        imperative: format!("{}{}", base, imperative),
+        potential: format!("{}{}る", base, e),
+        passive: format!("{}{}れる", base, a),
+        causative: format!("{}{}せる", base, a),
+        causative_passive: format!("{}{}せられる", base, a),
+    })
+}
+
+/// Returns `true` for 行く and its compounds like 出ていく, which use って instead of いて. Written
+/// in kana, いく is only taken as 行く on its own or after て and で
+fn is_iku(lemma: &str) -> bool {
+    if lemma.ends_with("行く") || lemma.ends_with("逝く") {
+        return true;
+    }
+
+    match lemma.strip_suffix("いく") {
+        Some(base) => base.is_empty() || base.ends_with('て') || base.ends_with('で'),
+        None => false,
+    }
+}
+
+/// Returns the kana of the あ, い, う, え and お columns of a godan row
+fn row_kana(row: SyllableRow) -> Option<[char; 5]> {
+    Some(match row {
+        SyllableRow::K => ['か', 'き', 'く', 'け', 'こ'],
+        SyllableRow::G => ['が', 'ぎ', 'ぐ', 'げ', 'ご'],
+        SyllableRow::S => ['さ', 'し', 'す', 'せ', 'そ'],
+        SyllableRow::T => ['た', 'ち', 'つ', 'て', 'と'],
+        SyllableRow::N => ['な', 'に', 'ぬ', 'ね', 'の'],
+        SyllableRow::B => ['ば', 'び', 'ぶ', 'べ', 'ぼ'],
+        SyllableRow::M => ['ま', 'み', 'む', 'め', 'も'],
+        SyllableRow::R => ['ら', 'り', 'る', 'れ', 'ろ'],
+        SyllableRow::Wa => ['わ', 'い', 'う', 'え', 'お'],
+        _ => return None,
+    })
+}
+
+/// Applies the register to a form which conjungates like an ichidan verb
+fn ichidan_register(plain: String, register: Register) -> String {
+    match register {
+        Register::Plain => plain,
+        Register::Polite => format!("{}ます", plain.strip_suffix('る').unwrap_or(&plain)),
+    }
+}
+
+impl<'a> AdjectiveType<'a> {
+    /// Conjungates `lemma`, the dictionary form of an adjective of this type without だ. Returns
+    /// `None` for inflections adjectives don't have, like the passive
+    pub fn conjungate(
+        &self,
+        lemma: &str,
+        inflection: Inflection,
+        register: Register,
+    ) -> Option<String> {
+        let polite = register == Register::Polite;
+
+        match self {
+            Self::I => {
+                // いい and its compounds like かっこいい conjungate from よい
+                let base = match lemma.strip_suffix("いい") {
+                    Some(base) if !NOT_II_COMPOUNDS.iter().any(|word| lemma.ends_with(word)) => {
+                        format!("{}よ", base)
+                    }
+                    _ => lemma.strip_suffix('い')?.to_string(),
+                };
+
+                Some(match inflection {
+                    Inflection::Dictionary if polite => format!("{}です", lemma),
+                    Inflection::Dictionary => lemma.to_string(),
+                    Inflection::Negative if polite => format!("{}くないです", base),
+                    Inflection::Negative => format!("{}くない", base),
+                    Inflection::Te if polite => return None,
+                    Inflection::Te => format!("{}くて", base),
+                    Inflection::Past if polite => format!("{}かったです", base),
+                    Inflection::Past => format!("{}かった", base),
+                    Inflection::Conditional if polite => return None,
+                    Inflection::Conditional => format!("{}ければ", base),
+                    Inflection::Volitional if polite => format!("{}でしょう", lemma),
+                    Inflection::Volitional => format!("{}かろう", base),
+                    _ => return None,
+                })
+            }
+            Self::Na => Some(match inflection {
+                Inflection::Dictionary if polite => format!("{}です", lemma),
+                Inflection::Dictionary => format!("{}だ", lemma),
+                Inflection::Negative if polite => format!("{}ではありません", lemma),
+                Inflection::Negative => format!("{}ではない", lemma),
+                Inflection::Te if polite => format!("{}でして", lemma),
+                Inflection::Te => format!("{}で", lemma),
+                Inflection::Past if polite => format!("{}でした", lemma),
+                Inflection::Past => format!("{}だった", lemma),
+                Inflection::Conditional if polite => return None,
+                Inflection::Conditional => format!("{}なら", lemma),
+                Inflection::Volitional if polite => format!("{}でしょう", lemma),
+                Inflection::Volitional => format!("{}だろう", lemma),
+                _ => return None,
+            }),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(verb: VerbType, lemma: &str, inflection: Inflection) -> String {
+        verb.conjungate(lemma, inflection, Register::Plain).unwrap()
+    }
+
+    fn polite(verb: VerbType, lemma: &str, inflection: Inflection) -> String {
+        verb.conjungate(lemma, inflection, Register::Polite)
+            .unwrap()
+    }
+
+    #[test]
+    fn godan_te_and_past_forms() {
+        for (row, lemma, te, past) in [
+            (SyllableRow::K, "書く", "書いて", "書いた"),
+            (SyllableRow::G, "泳ぐ", "泳いで", "泳いだ"),
+            (SyllableRow::S, "話す", "話して", "話した"),
+            (SyllableRow::T, "待つ", "待って", "待った"),
+            (SyllableRow::N, "死ぬ", "死んで", "死んだ"),
+            (SyllableRow::B, "遊ぶ", "遊んで", "遊んだ"),
+            (SyllableRow::M, "読む", "読んで", "読んだ"),
+            (SyllableRow::R, "帰る", "帰って", "帰った"),
+            (SyllableRow::Wa, "買う", "買って", "買った"),
+            (SyllableRow::K, "行く", "行って", "行った"),
+            (SyllableRow::K, "出ていく", "出ていって", "出ていった"),
+            (SyllableRow::K, "持って行く", "持って行って", "持って行った"),
+            (SyllableRow::K, "いく", "いって", "いった"),
+            (SyllableRow::K, "飛んでいく", "飛んでいって", "飛んでいった"),
+            (SyllableRow::K, "ひく", "ひいて", "ひいた"),
+            // Only a kana いく on its own or after て and で is 行く
+            (SyllableRow::K, "老いく", "老いいて", "老いいた"),
+            (SyllableRow::Wa, "問う", "問うて", "問うた"),
+            (SyllableRow::Wa, "請う", "請うて", "請うた"),
+            (SyllableRow::Wa, "こう", "こうて", "こうた"),
+            (SyllableRow::Wa, "使う", "使って", "使った"),
+        ] {
+            assert_eq!(plain(VerbType::Godan(row), lemma, Inflection::Te), te);
+            assert_eq!(plain(VerbType::Godan(row), lemma, Inflection::Past), past);
+        }
+    }
+
+    #[test]
+    fn godan_forms() {
+        let verb = VerbType::Godan(SyllableRow::K);
+        let expected = [
+            (Inflection::Dictionary, "書く", "書きます"),
+            (Inflection::Negative, "書かない", "書きません"),
+            (Inflection::Te, "書いて", "書きまして"),
+            (Inflection::Past, "書いた", "書きました"),
+            (Inflection::Conditional, "書けば", "書きましたら"),
+            (Inflection::Volitional, "書こう", "書きましょう"),
+            (Inflection::Potential, "書ける", "書けます"),
+            (Inflection::Passive, "書かれる", "書かれます"),
+            (Inflection::Causative, "書かせる", "書かせます"),
+            (
+                Inflection::CausativePassive,
+                "書かせられる",
+                "書かせられます",
+            ),
+            (Inflection::Imperative, "書け", "書いてください"),
+        ];
+
+        for (inflection, plain_form, polite_form) in expected {
+            assert_eq!(plain(verb, "書く", inflection), plain_form);
+            assert_eq!(polite(verb, "書く", inflection), polite_form);
+        }
+
+        assert_eq!(
+            plain(
+                VerbType::Godan(SyllableRow::Wa),
+                "買う",
+                Inflection::Negative
+            ),
+            "買わない"
+        );
+        assert_eq!(
+            plain(
+                VerbType::Godan(SyllableRow::R),
+                "ある",
+                Inflection::Negative
+            ),
+            "ない"
+        );
+        assert_eq!(
+            polite(
+                VerbType::Godan(SyllableRow::R),
+                "いらっしゃる",
+                Inflection::Dictionary
+            ),
+            "いらっしゃいます"
+        );
+    }
+
+    #[test]
+    fn ichidan_and_irregular_forms() {
+        let ichidan = VerbType::Ichidan(SyllableRow::B);
+        assert_eq!(plain(ichidan, "食べる", Inflection::Negative), "食べない");
+        assert_eq!(
+            plain(ichidan, "食べる", Inflection::Potential),
+            "食べられる"
+        );
+        assert_eq!(plain(ichidan, "食べる", Inflection::Imperative), "食べろ");
+
+        assert_eq!(
+            plain(VerbType::Suru, "勉強する", Inflection::Past),
+            "勉強した"
+        );
+        assert_eq!(
+            plain(VerbType::Suru, "する", Inflection::Potential),
+            "できる"
+        );
+        assert_eq!(
+            plain(VerbType::Kuru, "来る", Inflection::Negative),
+            "来ない"
+        );
+        assert_eq!(
+            plain(VerbType::Kuru, "くる", Inflection::Negative),
+            "こない"
+        );
+        assert_eq!(
+            plain(VerbType::Kuru, "くる", Inflection::Conditional),
+            "くれば"
+        );
+        assert_eq!(
+            plain(VerbType::Zuru, "感ずる", Inflection::Negative),
+            "感じない"
+        );
+        assert_eq!(
+            plain(VerbType::Zuru, "感ずる", Inflection::Conditional),
+            "感ずれば"
+        );
+        assert_eq!(
+            polite(VerbType::Zuru, "感ずる", Inflection::Past),
+            "感じました"
+        );
+
+        assert_eq!(
+            VerbType::Suru.conjungate("食べる", Inflection::Te, Register::Plain),
+            None
+        );
+    }
+
+    #[test]
+    fn suru_verbs_of_a_single_kanji() {
+        let expected = [
+            (Inflection::Negative, "愛さない", "勉強しない"),
+            (Inflection::Te, "愛して", "勉強して"),
+            (Inflection::Conditional, "愛すれば", "勉強すれば"),
+            (Inflection::Volitional, "愛そう", "勉強しよう"),
+            (Inflection::Potential, "愛せる", "勉強できる"),
+            (Inflection::Passive, "愛される", "勉強される"),
+            (Inflection::Causative, "愛させる", "勉強させる"),
+            (Inflection::Imperative, "愛せよ", "勉強しろ"),
+        ];
+
+        for (inflection, single, noun) in expected {
+            assert_eq!(plain(VerbType::Suru, "愛する", inflection), single);
+            assert_eq!(plain(VerbType::Suru, "勉強する", inflection), noun);
+        }
+
+        assert_eq!(
+            polite(VerbType::Suru, "愛する", Inflection::Negative),
+            "愛しません"
+        );
+        // Kana stems are not kanji
+        assert_eq!(
+            plain(VerbType::Suru, "ずする", Inflection::Negative),
+            "ずしない"
+        );
+        assert_eq!(
+            plain(VerbType::Suru, "する", Inflection::Negative),
+            "しない"
+        );
+        assert_eq!(
+            VerbType::Suru.conjungate("愛す", Inflection::Te, Register::Plain),
+            None
+        );
+    }
+
+    #[test]
+    fn adjective_forms() {
+        let conjungate = |lemma, inflection, register| {
+            AdjectiveType::I
+                .conjungate(lemma, inflection, register)
+                .unwrap()
+        };
+
+        assert_eq!(
+            conjungate("高い", Inflection::Negative, Register::Plain),
+            "高くない"
+        );
+        assert_eq!(
+            conjungate("高い", Inflection::Past, Register::Polite),
+            "高かったです"
+        );
+        assert_eq!(
+            conjungate("いい", Inflection::Negative, Register::Plain),
+            "よくない"
+        );
+        assert_eq!(
+            conjungate("いい", Inflection::Dictionary, Register::Polite),
+            "いいです"
+        );
+        assert_eq!(
+            conjungate("かっこいい", Inflection::Negative, Register::Plain),
+            "かっこよくない"
+        );
+        assert_eq!(
+            conjungate("かっこいい", Inflection::Past, Register::Plain),
+            "かっこよかった"
+        );
+        assert_eq!(
+            conjungate("かわいい", Inflection::Negative, Register::Plain),
+            "かわいくない"
+        );
+        assert_eq!(
+            conjungate("可愛いい", Inflection::Negative, Register::Plain),
+            "可愛いくない"
+        );
+        assert_eq!(
+            conjungate("小かわいい", Inflection::Past, Register::Plain),
+            "小かわいかった"
+        );
+        assert_eq!(
+            conjungate("気持ちいい", Inflection::Negative, Register::Plain),
+            "気持ちよくない"
+        );
+        assert_eq!(
+            conjungate("良い", Inflection::Conditional, Register::Plain),
+            "良ければ"
+        );
+
+        assert_eq!(
+            AdjectiveType::Na.conjungate("静か", Inflection::Past, Register::Polite),
+            Some("静かでした".to_string())
+        );
+        assert_eq!(
+            AdjectiveType::I.conjungate("高い", Inflection::Passive, Register::Plain),
+            None
+        );
+    }
+}
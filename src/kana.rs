@@ -0,0 +1,349 @@
+//! Conversion between hiragana, katakana and their half width forms, and classification of
+//! characters by script
+
+use crate::Morpheme;
+
+/// Offset between a hiragana and its katakana codepoint
+const KATAKANA_OFFSET: u32 = 0x60;
+
+/// Offset between a full width ASCII character and its ASCII codepoint
+const FULL_WIDTH_OFFSET: u32 = 0xFEE0;
+
+/// Combining voiced sound mark, used for ヷ, ヸ, ヹ and ヺ which have no hiragana codepoint
+const COMBINING_VOICED: char = '\u{3099}';
+
+/// Half width katakana and symbols from U+FF61 to U+FF9F
+const HALF_WIDTH: &str = "｡｢｣､･ｦｧｨｩｪｫｬｭｮｯｰｱｲｳｴｵｶｷｸｹｺｻｼｽｾｿﾀﾁﾂﾃﾄﾅﾆﾇﾈﾉﾊﾋﾌﾍﾎﾏﾐﾑﾒﾓﾔﾕﾖﾗﾘﾙﾚﾛﾜﾝﾞﾟ";
+
+/// The full width forms of [`HALF_WIDTH`], in the same order
+const FULL_WIDTH: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+
+/// Katakana which have a voiced form at the next codepoint
+const VOICEABLE: &str = "カキクケコサシスセソタチツテトハヒフヘホ";
+
+/// The script a character belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Script {
+    /// Kanji, including the iteration mark 々 and 〆
+    Kanji,
+    Hiragana,
+    /// Full and half width katakana, including the prolonged sound mark ー
+    Katakana,
+    /// Latin letters, in ASCII, full width or with diacritics
+    Latin,
+    /// ASCII and full width digits
+    Digit,
+    /// Punctuation, symbols and whitespace
+    Symbol,
+    /// Letters of other scripts
+    Other,
+}
+
+/// Returns the script `c` belongs to
+pub fn script(c: char) -> Script {
+    match c {
+        _ if is_kanji(c) => Script::Kanji,
+        _ if is_hiragana(c) => Script::Hiragana,
+        _ if is_katakana(c) || is_half_width_katakana(c) => Script::Katakana,
+        '0'..='9' | '０'..='９' => Script::Digit,
+        'a'..='z' | 'A'..='Z' | 'ａ'..='ｚ' | 'Ａ'..='Ｚ' => Script::Latin,
+        '\u{C0}'..='\u{24F}' if c.is_alphabetic() => Script::Latin,
+        _ if c.is_alphanumeric() => Script::Other,
+        _ => Script::Symbol,
+    }
+}
+
+/// Returns `true` if `c` is a kanji, including the iteration mark 々 and 〆
+pub fn is_kanji(c: char) -> bool {
+    matches!(c,
+        '\u{4E00}'..='\u{9FFF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FA1F}'
+        | '々'
+        | '〆'
+        | '〇')
+}
+
+/// Returns `true` if `c` is a hiragana
+pub fn is_hiragana(c: char) -> bool {
+    matches!(c, '\u{3041}'..='\u{3096}' | 'ゝ' | 'ゞ')
+}
+
+/// Returns `true` if `c` is a full width katakana, including the prolonged sound mark
+pub fn is_katakana(c: char) -> bool {
+    matches!(c, '\u{30A1}'..='\u{30FA}' | 'ー' | 'ヽ' | 'ヾ' | '\u{31F0}'..='\u{31FF}')
+}
+
+/// Returns `true` if `c` is a half width katakana, including the prolonged sound mark and the
+/// voiced sound marks
+pub fn is_half_width_katakana(c: char) -> bool {
+    matches!(c, '\u{FF66}'..='\u{FF9F}')
+}
+
+/// Returns `true` if `c` is a hiragana or katakana
+pub fn is_kana(c: char) -> bool {
+    is_hiragana(c) || is_katakana(c)
+}
+
+/// Returns `true` if `s` is not empty and consists of hiragana and katakana only
+pub fn is_kana_only(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(is_kana)
+}
+
+/// Converts a hiragana into its katakana, including ゔ and small kana. Other characters are
+/// returned unchanged
+pub fn hiragana_to_katakana_char(c: char) -> char {
+    match c {
+        '\u{3041}'..='\u{3096}' | 'ゝ' | 'ゞ' => {
+            char::from_u32(c as u32 + KATAKANA_OFFSET).unwrap_or(c)
+        }
+        _ => c,
+    }
+}
+
+/// Converts a katakana into its hiragana, including ヴ and small kana. Other characters, like
+/// ヷ which has no single hiragana, are returned unchanged
+pub fn katakana_to_hiragana_char(c: char) -> char {
+    match c {
+        '\u{30A1}'..='\u{30F6}' | 'ヽ' | 'ヾ' => {
+            char::from_u32(c as u32 - KATAKANA_OFFSET).unwrap_or(c)
+        }
+        _ => c,
+    }
+}
+
+/// Converts all hiragana in `s` into katakana
+pub fn hiragana_to_katakana(s: &str) -> String {
+    s.chars().map(hiragana_to_katakana_char).collect()
+}
+
+/// Converts all katakana in `s` into hiragana. ヷ, ヸ, ヹ and ヺ become わ, ゐ, ゑ and を
+/// followed by a combining voiced sound mark
+pub fn katakana_to_hiragana(s: &str) -> String {
+    let mut hiragana = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        let unvoiced = match c {
+            'ヷ' => 'わ',
+            'ヸ' => 'ゐ',
+            'ヹ' => 'ゑ',
+            'ヺ' => 'を',
+            _ => {
+                hiragana.push(katakana_to_hiragana_char(c));
+                continue;
+            }
+        };
+        hiragana.push(unvoiced);
+        hiragana.push(COMBINING_VOICED);
+    }
+
+    hiragana
+}
+
+/// Converts half width katakana and ASCII in `s` into their full width forms. Half width voiced
+/// sound marks are merged into the katakana before them, so ｶﾞ becomes ガ
+pub fn to_full_width(s: &str) -> String {
+    let mut full = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let c = ascii_to_full_width(half_to_full_width_kana(c));
+        let merged = match chars.peek() {
+            Some('ﾞ') => voiced(c),
+            Some('ﾟ') => semi_voiced(c),
+            _ => None,
+        };
+
+        match merged {
+            Some(merged) => {
+                full.push(merged);
+                chars.next();
+            }
+            None => full.push(c),
+        }
+    }
+
+    full
+}
+
+/// Converts full width katakana and ASCII in `s` into their half width forms. Voiced katakana
+/// are split into the katakana and a voiced sound mark, so ガ becomes ｶﾞ. Hiragana, which have no
+/// half width form, are kept
+pub fn to_half_width(s: &str) -> String {
+    let mut half = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        if let Some(i) = FULL_WIDTH.chars().position(|full| full == c) {
+            half.extend(HALF_WIDTH.chars().nth(i));
+            continue;
+        }
+
+        let unvoiced = unvoiced(c).and_then(|(base, mark)| {
+            let i = FULL_WIDTH.chars().position(|full| full == base)?;
+            Some((HALF_WIDTH.chars().nth(i)?, mark))
+        });
+        match unvoiced {
+            Some((base, mark)) => {
+                half.push(base);
+                half.push(mark);
+            }
+            None => half.push(full_width_to_ascii(c)),
+        }
+    }
+
+    half
+}
+
+/// Converts half width katakana into full width and full width ASCII into ASCII, the form most
+/// dictionaries expect
+pub fn normalize_width(s: &str) -> String {
+    to_full_width(s).chars().map(full_width_to_ascii).collect()
+}
+
+/// Converts a half width katakana or symbol into its full width form. Other characters are
+/// returned unchanged
+pub(crate) fn half_to_full_width_kana(c: char) -> char {
+    match HALF_WIDTH.chars().position(|half| half == c) {
+        Some(i) => FULL_WIDTH.chars().nth(i).unwrap_or(c),
+        None => c,
+    }
+}
+
+/// Converts a printable ASCII character or space into its full width form
+fn ascii_to_full_width(c: char) -> char {
+    match c {
+        ' ' => '\u{3000}',
+        '!'..='~' => char::from_u32(c as u32 + FULL_WIDTH_OFFSET).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Converts a full width ASCII character or ideographic space into ASCII
+pub(crate) fn full_width_to_ascii(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '！'..='～' => char::from_u32(c as u32 - FULL_WIDTH_OFFSET).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Returns the voiced form of a full width katakana, like ガ for カ
+pub(crate) fn voiced(c: char) -> Option<char> {
+    match c {
+        'ウ' => Some('ヴ'),
+        'ワ' => Some('ヷ'),
+        'ヲ' => Some('ヺ'),
+        _ if VOICEABLE.contains(c) => char::from_u32(c as u32 + 1),
+        _ => None,
+    }
+}
+
+/// Returns the semi voiced form of a full width katakana, like パ for ハ
+pub(crate) fn semi_voiced(c: char) -> Option<char> {
+    match c {
+        'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' => char::from_u32(c as u32 + 2),
+        _ => None,
+    }
+}
+
+/// Splits a voiced or semi voiced full width katakana into its base and half width sound mark
+fn unvoiced(c: char) -> Option<(char, char)> {
+    match c {
+        'ヴ' => return Some(('ウ', 'ﾞ')),
+        'ヷ' => return Some(('ワ', 'ﾞ')),
+        'ヺ' => return Some(('ヲ', 'ﾞ')),
+        _ => {}
+    }
+
+    let base = |offset| (c as u32).checked_sub(offset).and_then(char::from_u32);
+    let one = base(1).filter(|base| voiced(*base) == Some(c));
+    let two = base(2).filter(|base| semi_voiced(*base) == Some(c));
+    one.map(|base| (base, 'ﾞ'))
+        .or_else(|| two.map(|base| (base, 'ﾟ')))
+}
+
+impl<'dict, 'input> Morpheme<'dict, 'input> {
+    /// Returns the reading of the morpheme as written, in hiragana, like とうきょう for 東京
+    pub fn reading_hiragana(&self) -> String {
+        katakana_to_hiragana(self.kana_reading())
+    }
+
+    /// Returns `true` if the surface of the morpheme is written in kana only
+    pub fn is_kana_only(&self) -> bool {
+        is_kana_only(self.surface)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hiragana_and_katakana() {
+        assert_eq!(hiragana_to_katakana("ゔぁいおりん"), "ヴァイオリン");
+        assert_eq!(katakana_to_hiragana("ヴァイオリン"), "ゔぁいおりん");
+        assert_eq!(hiragana_to_katakana("ゕゖゝゞ"), "ヵヶヽヾ");
+        assert_eq!(katakana_to_hiragana("ヵヶヽヾ"), "ゕゖゝゞ");
+
+        // ヷ to ヺ have no hiragana codepoint
+        for c in ['ヷ', 'ヸ', 'ヹ', 'ヺ'] {
+            assert_eq!(katakana_to_hiragana_char(c), c);
+        }
+        assert_eq!(katakana_to_hiragana("ヷヺ"), "わ\u{3099}を\u{3099}");
+
+        // The prolonged sound mark is used with both scripts
+        assert_eq!(katakana_to_hiragana("ラーメン"), "らーめん");
+        assert_eq!(hiragana_to_katakana("らーめん"), "ラーメン");
+        assert_eq!(katakana_to_hiragana("漢字abc"), "漢字abc");
+    }
+
+    #[test]
+    fn widths() {
+        assert_eq!(to_full_width("ｶﾞｷﾞﾊﾟｳﾞﾜﾞｱ"), "ガギパヴヷア");
+        assert_eq!(to_full_width("ﾗｰﾒﾝ｡"), "ラーメン。");
+        assert_eq!(to_full_width("ABC 1!"), "ＡＢＣ　１！");
+        assert_eq!(to_half_width("ガパヴヷ"), "ｶﾞﾊﾟｳﾞﾜﾞ");
+        assert_eq!(to_half_width("ラーメン。ＡＢＣ　１"), "ﾗｰﾒﾝ｡ABC 1");
+        // Hiragana and kanji have no half width form
+        assert_eq!(to_half_width("ひらがな漢字"), "ひらがな漢字");
+        assert_eq!(normalize_width("ｶﾞｯｺｳＡＢＣ１２"), "ガッコウABC12");
+    }
+
+    #[test]
+    fn sound_marks_on_unvoiceable_kana() {
+        assert_eq!(voiced('カ'), Some('ガ'));
+        assert_eq!(semi_voiced('ハ'), Some('パ'));
+        assert_eq!(voiced('ア'), None);
+        assert_eq!(voiced('ナ'), None);
+        assert_eq!(voiced('か'), None);
+        assert_eq!(semi_voiced('カ'), None);
+        assert_eq!(semi_voiced('パ'), None);
+
+        // Marks which can't be merged are kept as full width marks
+        assert_eq!(to_full_width("ｱﾞｶﾟ"), "ア゛カ゜");
+        assert_eq!(to_half_width("ア"), "ｱ");
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(script('々'), Script::Kanji);
+        assert_eq!(script('〆'), Script::Kanji);
+        assert_eq!(script('𠮷'), Script::Kanji);
+        // ヶ is a small katakana even when used as a counter like in 三ヶ月
+        assert_eq!(script('ヶ'), Script::Katakana);
+        assert_eq!(script('ー'), Script::Katakana);
+        assert_eq!(script('ｶ'), Script::Katakana);
+        assert_eq!(script('ゝ'), Script::Hiragana);
+        assert_eq!(script('ａ'), Script::Latin);
+        assert_eq!(script('é'), Script::Latin);
+        assert_eq!(script('７'), Script::Digit);
+        assert_eq!(script('。'), Script::Symbol);
+        assert_eq!(script('한'), Script::Other);
+
+        assert!(is_kana_only("ラーメンです"));
+        assert!(!is_kana_only("ラーメン屋"));
+        assert!(!is_kana_only(""));
+    }
+}
@@ -0,0 +1,476 @@
+//! The lattice of all words of the system and user dictionary within a text.
+//!
+//! It is built like igo's `Tagger` builds its lattice, so the cheapest path through it is the
+//! segmentation igo returns for the same dictionary. Positions are counted in UTF-16 code units,
+//! like all keys of the dictionary.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+use crate::{
+    dictionary::Dictionary,
+    user_dict::{UserWord, UserWords},
+    RawMorpheme,
+};
+
+/// A word of the lattice
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum WordId {
+    /// The beginning or end of the text
+    Boundary,
+    /// A word of the system dictionary
+    System(usize),
+    /// A word of the user dictionary
+    User(usize),
+}
+
+/// A word placed at a position of the text
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Node {
+    pub(crate) word: WordId,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) left_id: i16,
+    pub(crate) right_id: i16,
+    pub(crate) cost: i32,
+    /// Cost of the cheapest path from the beginning of the text up to and including the node
+    pub(crate) total: i32,
+    /// Index of the node before this one on the cheapest path
+    prev: usize,
+}
+
+impl Node {
+    fn boundary(position: usize) -> Self {
+        Node {
+            word: WordId::Boundary,
+            start: position,
+            end: position,
+            left_id: 0,
+            right_id: 0,
+            cost: 0,
+            total: 0,
+            prev: 0,
+        }
+    }
+}
+
+pub(crate) struct Lattice<'a> {
+    dictionary: &'a Dictionary,
+    user: Option<&'a UserWords>,
+    units: Vec<u16>,
+    /// Byte offset of each code unit position which is a character boundary
+    offsets: Vec<Option<usize>>,
+    /// All nodes, starting with the beginning of the text and ending with its end
+    nodes: Vec<Node>,
+    /// Indices of the nodes which may precede a node starting at each position. Spaces are not
+    /// part of the lattice, so the nodes before a space may also precede the nodes after it
+    prevs: Vec<Vec<usize>>,
+    /// Whether a node or space was added since the last position was searched
+    added: bool,
+}
+
+impl<'a> Lattice<'a> {
+    pub(crate) fn new(dictionary: &'a Dictionary, user: Option<&'a UserWords>, text: &str) -> Self {
+        let units: Vec<u16> = text.encode_utf16().collect();
+        let mut offsets = vec![None; units.len() + 1];
+        let mut position = 0;
+        for (offset, c) in text.char_indices() {
+            offsets[position] = Some(offset);
+            position += c.len_utf16();
+        }
+        offsets[units.len()] = Some(text.len());
+
+        let mut lattice = Lattice {
+            dictionary,
+            user,
+            prevs: vec![Vec::new(); units.len() + 1],
+            units,
+            offsets,
+            nodes: vec![Node::boundary(0)],
+            added: false,
+        };
+        lattice.prevs[0].push(0);
+
+        for start in 0..lattice.units.len() {
+            if !lattice.prevs[start].is_empty() {
+                lattice.add_words(start);
+            }
+        }
+
+        let end = lattice.units.len();
+        lattice.add(Node::boundary(end), false);
+        lattice
+    }
+
+    /// Returns the cost of `node` following the node `prev`, including the cost of `node`
+    fn step_cost(&self, prev: &Node, node: &Node) -> i32 {
+        self.dictionary.link_cost(prev.right_id, node.left_id) + node.cost
+    }
+
+    /// Returns the indices of the nodes on the cheapest path, without the beginning and end of the
+    /// text, along with its cost
+    pub(crate) fn best_path(&self) -> (Vec<usize>, i32) {
+        let end = self.nodes.len() - 1;
+        let mut path = Vec::new();
+        let mut node = self.nodes[end].prev;
+        while node != 0 {
+            path.push(node);
+            node = self.nodes[node].prev;
+        }
+
+        path.reverse();
+        (path, self.nodes[end].total)
+    }
+
+    /// Returns the `n` cheapest paths like [`Lattice::best_path`], cheapest first. Paths are
+    /// searched backwards from the end of the text, using the cost of the cheapest path up to each
+    /// node as the estimate of the remaining cost, so each path found is the next cheapest one.
+    /// Paths of the same words at the same positions are only returned once
+    pub(crate) fn best_paths(&self, n: usize) -> Vec<(Vec<usize>, i32)> {
+        /// A path from a node to the end of the text
+        struct Hypothesis {
+            node: usize,
+            /// Cost of the path after the node, including the cost of the nodes after it
+            suffix: i32,
+            /// Index of the hypothesis of the node after this one
+            next: Option<usize>,
+        }
+
+        let end = self.nodes.len() - 1;
+        let mut hypotheses = vec![Hypothesis {
+            node: end,
+            suffix: 0,
+            next: None,
+        }];
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((self.nodes[end].total, 0)));
+
+        let mut paths = Vec::new();
+        let mut seen = HashSet::new();
+        while paths.len() < n {
+            let (cost, index) = match queue.pop() {
+                Some(Reverse(entry)) => entry,
+                None => break,
+            };
+
+            let node = hypotheses[index].node;
+            if node == 0 {
+                let mut path = Vec::new();
+                let mut next = hypotheses[index].next;
+                while let Some(hypothesis) = next.map(|next| &hypotheses[next]) {
+                    if hypothesis.node != end {
+                        path.push(hypothesis.node);
+                    }
+                    next = hypothesis.next;
+                }
+
+                let words: Vec<_> = path
+                    .iter()
+                    .map(|&index| {
+                        let node = &self.nodes[index];
+                        (node.start, node.end, node.word)
+                    })
+                    .collect();
+                if seen.insert(words) {
+                    paths.push((path, cost));
+                }
+                continue;
+            }
+
+            let current = &self.nodes[node];
+            for &prev in &self.prevs[current.start] {
+                let suffix = hypotheses[index].suffix + self.step_cost(&self.nodes[prev], current);
+                queue.push(Reverse((self.nodes[prev].total + suffix, hypotheses.len())));
+                hypotheses.push(Hypothesis {
+                    node: prev,
+                    suffix,
+                    next: Some(index),
+                });
+            }
+        }
+
+        paths
+    }
+
+    /// Returns the morphemes of the nodes `path`. `text` has to be the text of the lattice
+    pub(crate) fn morphemes<'text>(
+        &self,
+        text: &'text str,
+        path: &[usize],
+    ) -> Vec<RawMorpheme<'a, 'text>> {
+        path.iter()
+            .map(|&index| {
+                let node = &self.nodes[index];
+                let (start, end) = (self.offset(node.start), self.offset(node.end));
+                let feature = match node.word {
+                    WordId::System(word) => self.dictionary.feature(word),
+                    WordId::User(word) => self.user_words().word(word).feature.as_str(),
+                    WordId::Boundary => "",
+                };
+                RawMorpheme {
+                    surface: &text[start..end],
+                    feature,
+                    start,
+                }
+            })
+            .collect()
+    }
+
+    fn offset(&self, position: usize) -> usize {
+        self.offsets[position].expect("nodes start and end at character boundaries")
+    }
+
+    fn user_words(&self) -> &'a UserWords {
+        self.user
+            .expect("user words are only added with a user dictionary")
+    }
+
+    /// Adds the known and unknown words starting at `start`
+    fn add_words(&mut self, start: usize) {
+        let nodes = self.nodes.len();
+        self.added = false;
+
+        let mut found = Vec::new();
+        if let Some(user) = self.user {
+            user.common_prefix(&self.units[start..], |len, word| found.push((len, word)));
+            for (len, word) in found.drain(..) {
+                let node = user_node(user.word(word), word, start, start + len);
+                self.add(node, false);
+            }
+        }
+
+        let dictionary = self.dictionary;
+        dictionary
+            .trie()
+            .common_prefix(&self.units[start..], |len, id| found.push((len, id)));
+        for (len, id) in found.drain(..) {
+            for word in dictionary.words(id) {
+                self.add(system_node(dictionary, word, start, start + len), false);
+            }
+        }
+
+        let known = self.nodes.len() > nodes;
+        self.add_unknown_words(start, known);
+
+        // Only broken character definitions create no word at all, in which case the character
+        // becomes an unknown word of its category so the text can still be segmented
+        if !self.added {
+            let category = dictionary.category(self.units[start]);
+            let end = (start + 1..=self.units.len())
+                .find(|&end| self.offsets[end].is_some())
+                .unwrap_or(self.units.len());
+            let word = dictionary.words(category.id).start;
+            self.add(system_node(dictionary, word, start, end), false);
+        }
+    }
+
+    /// Adds the unknown words starting at `start` the way igo's `Unknown::search` does. Unless
+    /// their category is invoked, they are only added if no `known` word starts there
+    fn add_unknown_words(&mut self, start: usize, known: bool) {
+        let dictionary = self.dictionary;
+        let first = self.units[start];
+        let category = dictionary.category(first);
+        if known && !category.invoke {
+            return;
+        }
+
+        let space = dictionary.is_space(&category);
+        let len = self.units.len();
+        let add = |lattice: &mut Self, end: usize| {
+            for word in dictionary.words(category.id) {
+                lattice.add(system_node(dictionary, word, start, end), space);
+            }
+        };
+
+        let limit = len.min(start + category.length);
+        let mut i = start;
+        while i < limit {
+            add(self, i + 1);
+            if i + 1 != limit && !dictionary.compatible(first, self.units[i + 1]) {
+                return;
+            }
+            i += 1;
+        }
+
+        if category.group && i < len {
+            let end = (i..len)
+                .find(|&end| !dictionary.compatible(first, self.units[end]))
+                .unwrap_or(len);
+            add(self, end);
+        }
+    }
+
+    /// Adds `node`, connecting it to the cheapest node before it. A `space` is not added, instead
+    /// the nodes before it may also precede the nodes after it
+    fn add(&mut self, mut node: Node, space: bool) {
+        if self.offsets[node.end].is_none() {
+            return;
+        }
+
+        self.added = true;
+        if space {
+            let prevs = self.prevs[node.start].clone();
+            self.prevs[node.end].extend(prevs);
+            return;
+        }
+
+        // Like igo, the first of several equally cheap nodes is kept
+        let mut best: Option<(i32, usize)> = None;
+        for &prev in &self.prevs[node.start] {
+            let cost = self.nodes[prev].total + self.step_cost(&self.nodes[prev], &node);
+            if !matches!(best, Some((min, _)) if min <= cost) {
+                best = Some((cost, prev));
+            }
+        }
+
+        let (total, prev) = best.expect("only reachable positions get nodes");
+        node.total = total;
+        node.prev = prev;
+        if node.word != WordId::Boundary {
+            self.prevs[node.end].push(self.nodes.len());
+        }
+        self.nodes.push(node);
+    }
+}
+
+fn system_node(dictionary: &Dictionary, word: usize, start: usize, end: usize) -> Node {
+    Node {
+        word: WordId::System(word),
+        start,
+        end,
+        left_id: dictionary.left_id(word),
+        right_id: dictionary.right_id(word),
+        cost: dictionary.cost(word) as i32,
+        total: 0,
+        prev: 0,
+    }
+}
+
+fn user_node(word: &UserWord, id: usize, start: usize, end: usize) -> Node {
+    Node {
+        word: WordId::User(id),
+        start,
+        end,
+        left_id: word.left_id,
+        right_id: word.right_id,
+        cost: word.cost as i32,
+        total: 0,
+        prev: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::fixture;
+
+    fn best(text: &str) -> (Vec<(usize, String)>, i32) {
+        let dictionary = Dictionary::load(fixture()).unwrap();
+        let lattice = Lattice::new(&dictionary, None, text);
+        let (path, cost) = lattice.best_path();
+        let morphemes = lattice
+            .morphemes(text, &path)
+            .iter()
+            .map(|raw| (raw.start, raw.surface.to_string()))
+            .collect();
+        (morphemes, cost)
+    }
+
+    fn surfaces(text: &str) -> Vec<String> {
+        best(text)
+            .0
+            .into_iter()
+            .map(|(_, surface)| surface)
+            .collect()
+    }
+
+    #[test]
+    fn cheapest_path_of_known_words() {
+        assert_eq!(
+            best("東京都庁").0,
+            vec![(0, "東京都".into()), (9, "庁".into())]
+        );
+        assert_eq!(best("東京都庁").1, 6500);
+        // Both connections between nouns and particles cost 100
+        assert_eq!(best("東京に").1, 3200);
+        assert_eq!(best(""), (vec![], 0));
+    }
+
+    #[test]
+    fn unknown_words() {
+        // Katakana is grouped, kanji is cut after at most two characters
+        assert_eq!(surfaces("スカイツリー"), vec!["スカイツリー"]);
+        assert_eq!(best("スカイツリー").1, 7000);
+        assert_eq!(surfaces("漢字"), vec!["漢字"]);
+        assert_eq!(
+            surfaces("東京スカイツリーに"),
+            vec!["東京", "スカイツリー", "に"]
+        );
+        // Unknown words of categories which aren't invoked only start where no known word does
+        assert_eq!(surfaces("東京都"), vec!["東京都"]);
+    }
+
+    #[test]
+    fn spaces_are_skipped() {
+        assert_eq!(
+            best("東京 に").0,
+            vec![(0, "東京".into()), (7, "に".into())]
+        );
+        assert_eq!(best("東京  に").1, best("東京に").1);
+    }
+
+    fn nbest(text: &str, n: usize) -> Vec<(Vec<String>, i32)> {
+        let dictionary = Dictionary::load(fixture()).unwrap();
+        let lattice = Lattice::new(&dictionary, None, text);
+        lattice
+            .best_paths(n)
+            .into_iter()
+            .map(|(path, cost)| {
+                let surfaces = lattice
+                    .morphemes(text, &path)
+                    .iter()
+                    .map(|raw| raw.surface.to_string())
+                    .collect();
+                (surfaces, cost)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn paths_by_cost() {
+        let paths = nbest("東京都庁", 10);
+        assert_eq!(
+            paths,
+            vec![
+                (vec!["東京都".into(), "庁".into()], 6500),
+                (vec!["東京".into(), "都".into(), "庁".into()], 11000),
+            ]
+        );
+        assert_eq!(paths[0], {
+            let (surfaces, cost) = best("東京都庁");
+            (surfaces.into_iter().map(|(_, s)| s).collect(), cost)
+        });
+        assert_eq!(nbest("東京都庁", 1).len(), 1);
+        assert_eq!(nbest("東京都庁", 0), vec![]);
+        assert_eq!(nbest("", 3), vec![(vec![], 0)]);
+    }
+
+    #[test]
+    fn paths_are_not_repeated() {
+        // Both the single space and its group end at the same position
+        let paths = nbest("東京 に", 10);
+        let mut unique = paths.clone();
+        unique.dedup();
+        assert_eq!(paths, unique);
+        assert!(paths.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(paths[0], (vec!["東京".into(), "に".into()], 3200));
+    }
+
+    #[test]
+    fn surrogate_pairs_are_not_split() {
+        assert_eq!(surfaces("東京𠮷"), vec!["東京", "𠮷"]);
+        assert_eq!(best("𠮷に").0, vec![(0, "𠮷".into()), (4, "に".into())]);
+    }
+}
@@ -1,93 +1,512 @@
-use std::{
-    convert::{TryFrom, TryInto},
-    path::Path,
+mod accent;
+mod batch;
+mod compile;
+mod deinflect;
+mod dictionary;
+mod error;
+mod features;
+mod furigana;
+mod inflection;
+pub mod kana;
+mod lattice;
+mod nbest;
+mod normalize;
+mod owned;
+mod position;
+mod romaji;
+mod ruby;
+mod sentence;
+mod stats;
+mod stream;
+mod trie;
+mod user_dict;
+mod word;
+
+pub use accent::{
+    accent_phrases, morae, pitch_pattern, AccentConnection, AccentModification, AccentPattern,
+    AccentPhrase, AccentRule, Pitch,
+};
+pub use compile::{
+    compile_dictionary, CompileProgress, CompileSummary, CHAR_FILE, LEX_FILE, MATRIX_FILE, UNK_FILE,
 };
+pub use deinflect::{deinflect, Deinflection, InflectionStep, Transformation};
+pub use dictionary::DictionaryBytes;
+pub use error::{CompileError, DictionaryError, FeatureError, ParseError, UserDictionaryError};
+pub use features::{FeatureColumn, UnidicFeatures};
+pub use furigana::furigana;
+pub use inflection::{Inflection, Register};
+pub use nbest::Analysis;
+pub use normalize::{normalize, NormalizeOptions, Normalized};
+pub use owned::{
+    AdjectiveTypeBuf, ConjungationBuf, ConjungationFormBuf, ConjungationKindBuf, MorphemeBuf,
+    NounTypeBuf, ParticleTypeBuf, SyllableRowBuf, VerbTypeBuf, WordClassBuf,
+};
+pub use position::{LineCol, LineIndex};
+pub use romaji::{kana_to_romaji, romanize, LongVowels, RomajiOptions, RomajiSystem};
+pub use ruby::{render_ruby, RubyFormat, RubyOptions};
+pub use sentence::{split_sentences, Sentence, SentenceSpan};
+pub use stats::UnknownStats;
+pub use stream::{ParseIter, ReaderIter};
+pub use user_dict::UserDictionary;
+pub use word::{group_words, Word};
+
+use std::{convert::TryFrom, fs, path::Path, sync::Arc};
 
 use igo::Morpheme as IgoMorpheme;
 use igo::Tagger;
 
+use dictionary::Dictionary;
+use features::split_features;
+use user_dict::UserWords;
+
+/// Files an igo dictionary directory has to contain
+pub(crate) const DICTIONARY_FILES: &[&str] = &[
+    "word2id",
+    "word.dat",
+    "word.ary.idx",
+    "word.inf",
+    "matrix.bin",
+    "char.category",
+    "code2category",
+];
+
+/// A parser using a loaded dictionary. Cloning it is cheap, as all clones share the same
+/// dictionary. It is `Send` and `Sync`, so one parser can be used by several threads
 #[derive(Clone)]
 pub struct Parser {
-    parser: Tagger,
+    /// igo's tagger, which is missing for dictionaries igo can't load
+    parser: Option<Arc<Tagger>>,
+    dictionary: Arc<Dictionary>,
+    user_dictionary: Option<Arc<UserWords>>,
+    normalization: Option<NormalizeOptions>,
 }
 
 impl Parser {
-    pub fn new(path: &str) -> Result<Self, std::io::Error> {
-        let tagger = Tagger::new(Path::new(path))?;
-        Ok(Parser { parser: tagger })
+    /// Loads the dictionary in the directory `path`. Its files are mapped into memory and must
+    /// not be modified while the parser or one of its clones exists
+    pub fn new(path: &str) -> Result<Self, DictionaryError> {
+        let path = Path::new(path);
+        let dictionary = Dictionary::load(path)?;
+
+        let tagger = Tagger::new(path).map_err(|source| DictionaryError::Load {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(Parser {
+            parser: Some(Arc::new(tagger)),
+            dictionary: Arc::new(dictionary),
+            user_dictionary: None,
+            normalization: None,
+        })
+    }
+
+    /// Maps the dictionary in the directory `path` into memory without loading it into igo. All
+    /// processes mapping the same dictionary share its pages, so a large dictionary only takes
+    /// memory once. Like [`Parser::with_user_dictionary`], the parser builds its lattice itself.
+    ///
+    /// The files must not be modified while the parser or one of its clones exists, otherwise
+    /// parsing may read inconsistent data or crash.
+    pub fn from_mmap(path: &str) -> Result<Self, DictionaryError> {
+        let dictionary = Dictionary::load(Path::new(path))?;
+        Ok(Self::native(dictionary))
+    }
+
+    /// Uses the dictionary whose files are `bytes`, for example a dictionary embedded into the
+    /// binary. Like [`Parser::with_user_dictionary`], the parser builds its lattice itself
+    pub fn from_bytes(bytes: DictionaryBytes) -> Result<Self, DictionaryError> {
+        let dictionary = Dictionary::from_bytes(bytes)?;
+        Ok(Self::native(dictionary))
+    }
+
+    fn native(dictionary: Dictionary) -> Self {
+        Parser {
+            parser: None,
+            dictionary: Arc::new(dictionary),
+            user_dictionary: None,
+            normalization: None,
+        }
     }
 
+    /// Parses `text` into morphemes.
+    ///
+    /// # Panics
+    /// Panics if the dictionary returns a feature which can't be mapped. Use
+    /// [`Parser::try_parse`] to handle those cases.
     pub fn parse<'text, 'dict>(&'dict self, text: &'text str) -> Vec<Morpheme<'dict, 'text>> {
-        self.parser
-            .parse(text)
+        self.tag(text)
+            .into_iter()
+            .map(|raw| Morpheme::try_from_raw(raw).unwrap())
+            .collect()
+    }
+
+    /// Parses `text` into morphemes, returning an error for the first morpheme whose features
+    /// can't be mapped
+    pub fn try_parse<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+    ) -> Result<Vec<Morpheme<'dict, 'text>>, ParseError> {
+        self.tag(text)
             .into_iter()
-            .map(Morpheme::from)
+            .map(Morpheme::try_from_raw)
             .collect()
     }
+
+    /// Parses `text` into morphemes without failing. Feature values which can't be mapped are
+    /// kept as `Unknown` variants
+    pub fn parse_lenient<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+    ) -> Vec<Morpheme<'dict, 'text>> {
+        self.tag(text)
+            .into_iter()
+            .map(Morpheme::from_raw_lenient)
+            .collect()
+    }
+
+    /// Same as [`Parser::parse_lenient`] but records every parsed morpheme in `stats`
+    pub fn parse_lenient_with_stats<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+        stats: &mut UnknownStats<'dict>,
+    ) -> Vec<Morpheme<'dict, 'text>> {
+        let morphemes = self.parse_lenient(text);
+        stats.extend(&morphemes);
+        morphemes
+    }
+}
+
+/// Ensures all files of an igo dictionary are present in `path`
+pub(crate) fn check_dictionary(path: &Path) -> Result<(), DictionaryError> {
+    if !path.is_dir() {
+        return Err(DictionaryError::NotADirectory(path.to_path_buf()));
+    }
+
+    for name in DICTIONARY_FILES {
+        let file = path.join(name);
+        let meta = match fs::metadata(&file) {
+            Ok(meta) => meta,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Err(DictionaryError::MissingFile(file));
+            }
+            Err(source) => return Err(DictionaryError::Io { file, source }),
+        };
+
+        if !meta.is_file() {
+            return Err(DictionaryError::MissingFile(file));
+        }
+
+        if meta.len() == 0 {
+            return Err(DictionaryError::Corrupt(file));
+        }
+    }
+
+    Ok(())
 }
 
 #[derive(Clone, Debug, PartialEq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Morpheme<'dict, 'input> {
     pub surface: &'input str,
     pub basic: &'dict str,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub word_class: WordClass<'dict>,
-    pub conjungation: Conjungation,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub conjungation: Conjungation<'dict>,
     pub origin: Option<Origin>,
     pub reading: &'dict str,
     pub lexeme: &'dict str,
+    /// Byte offset of the morpheme within the parsed text, so the morpheme spans
+    /// [`Morpheme::byte_range`]
     pub start: usize,
+    /// The raw, comma separated UniDic feature string
+    pub feature: &'dict str,
 }
 
 impl<'dict, 'input> From<IgoMorpheme<'dict, 'input>> for Morpheme<'dict, 'input> {
     fn from(igo_morph: IgoMorpheme<'dict, 'input>) -> Morpheme<'dict, 'input> {
-        let features: &Vec<_> = &igo_morph.feature.split(',').collect();
+        Self::try_from_raw(igo_morph.into()).unwrap()
+    }
+}
+
+impl<'dict, 'input> Morpheme<'dict, 'input> {
+    /// Returns an owned copy of the morpheme which borrows neither from the dictionary nor
+    /// from the input
+    pub fn to_buf(&self) -> MorphemeBuf {
+        (*self).into()
+    }
+
+    /// Returns all UniDic feature columns of the morpheme
+    pub fn features(&self) -> UnidicFeatures<'dict> {
+        UnidicFeatures::from_feature(self.feature)
+    }
+
+    /// Returns all feature values which had no typed mapping and were kept as `Unknown`
+    pub fn unknown_features(&self) -> Vec<(FeatureColumn, &'dict str)> {
+        let mut unknown = Vec::new();
+        let values = [
+            self.word_class.unknown(),
+            self.conjungation.kind.unknown(),
+            self.conjungation.form.unknown(),
+        ];
 
-        let word_class: WordClass = features.try_into().unwrap();
+        // The syllable row of a verb is part of both, its word class and its conjungation kind
+        for value in values.iter().flatten() {
+            if !unknown.contains(value) {
+                unknown.push(*value);
+            }
+        }
+
+        unknown
+    }
 
-        let conjungation_form: ConjungationForm = features.try_into().unwrap();
-        let conjungation_kind: ConjungationKind = features.try_into().unwrap();
+    /// Returns `true` if any feature of the morpheme fell back to an `Unknown` variant
+    pub fn has_unknown(&self) -> bool {
+        self.word_class.unknown().is_some() || self.conjungation.unknown().is_some()
+    }
+
+    /// Converts a raw morpheme, failing if one of its features can't be mapped
+    fn try_from_raw(raw: RawMorpheme<'dict, 'input>) -> Result<Self, ParseError> {
+        let features = split_features(raw.feature);
+        let morph = Self::from_features(raw.surface, raw.start, raw.feature, &features);
+
+        match morph.unknown_features().first() {
+            Some(&unknown) => Err(unknown_error(&features, unknown).at(raw.surface, raw.start)),
+            None => Ok(morph),
+        }
+    }
+
+    /// Converts a raw morpheme, keeping features which can't be mapped as `Unknown`
+    fn from_raw_lenient(raw: RawMorpheme<'dict, 'input>) -> Self {
+        let features = split_features(raw.feature);
+        Self::from_features(raw.surface, raw.start, raw.feature, &features)
+    }
+
+    fn from_features(
+        surface: &'input str,
+        start: usize,
+        feature: &'dict str,
+        features: &[&'dict str],
+    ) -> Self {
+        let word_class = WordClass::from_features_lenient(features);
 
         let conjungation = Conjungation {
-            kind: conjungation_kind,
-            form: conjungation_form,
+            kind: ConjungationKind::from_features_lenient(features),
+            form: ConjungationForm::from_features_lenient(features),
         };
 
-        let origin = if features.len() > 12 {
-            Origin::from_string(features[12])
-        } else {
-            None
-        };
+        let origin = Origin::from_string(str_or_empty(features, FeatureColumn::Goshu.index()));
 
-        let basic = str_or_empty(features, 6);
-        let lexeme = str_or_empty(features, 10);
-        let reading = str_or_empty(features, 9);
+        let basic = str_or_empty(features, FeatureColumn::LForm.index());
+        let lexeme = str_or_empty(features, FeatureColumn::OrthBase.index());
+        let reading = str_or_empty(features, FeatureColumn::Pron.index());
 
         Morpheme {
-            start: igo_morph.start,
-            surface: igo_morph.surface,
+            start,
+            surface,
             basic,
             lexeme,
             reading,
             word_class,
             origin,
             conjungation,
+            feature,
+        }
+    }
+}
+
+/// A morpheme of the system or user dictionary whose features are not mapped yet
+#[derive(Clone, Copy, Debug)]
+struct RawMorpheme<'dict, 'input> {
+    surface: &'input str,
+    feature: &'dict str,
+    start: usize,
+}
+
+impl<'dict, 'input> From<IgoMorpheme<'dict, 'input>> for RawMorpheme<'dict, 'input> {
+    fn from(igo_morph: IgoMorpheme<'dict, 'input>) -> Self {
+        RawMorpheme {
+            surface: igo_morph.surface,
+            feature: igo_morph.feature,
+            start: igo_morph.start,
         }
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Copy)]
-pub struct Conjungation {
-    pub kind: ConjungationKind,
-    pub form: ConjungationForm,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Conjungation<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub kind: ConjungationKind<'a>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub form: ConjungationForm<'a>,
 }
 
+impl<'a> Conjungation<'a> {
+    /// Returns the first feature value which was kept as `Unknown`
+    pub fn unknown(&self) -> Option<(FeatureColumn, &'a str)> {
+        self.kind.unknown().or_else(|| self.form.unknown())
+    }
+}
+
+/// The conjungation type (cType) of a word, describing the paradigm it inflects by
 #[derive(Clone, Debug, PartialEq, Copy)]
-pub enum ConjungationKind {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConjungationKind<'a> {
+    /// The word does not conjungate (`*`)
     None,
+    /// 五段
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Godan(SyllableRow<'a>),
+    /// 上一段
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    KamiIchidan(SyllableRow<'a>),
+    /// 下一段
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    ShimoIchidan(SyllableRow<'a>),
+    /// 一段 without a distinction between 上 and 下, used by older dictionaries
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Ichidan(SyllableRow<'a>),
+    /// カ行変格
+    Kuru,
+    /// サ行変格
+    Suru,
+    /// ザ行変格
+    Zuru,
+    /// ラ行変格
+    IrregRu,
+    /// ナ行変格
+    IrregNu,
+    /// 形容詞
+    Adjective,
+    /// 助動詞, holding the name of the auxilary like `タ` or `マス`
+    Auxilary(&'a str),
+    /// 無変化型
+    Invariant,
+    /// 文語四段
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    ClassicalYodan(SyllableRow<'a>),
+    /// 文語上一段
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    ClassicalKamiIchidan(SyllableRow<'a>),
+    /// 文語上二段
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    ClassicalKamiNidan(SyllableRow<'a>),
+    /// 文語下一段
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    ClassicalShimoIchidan(SyllableRow<'a>),
+    /// 文語下二段
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    ClassicalShimoNidan(SyllableRow<'a>),
+    /// 文語カ行変格
+    ClassicalKuru,
+    /// 文語サ行変格
+    ClassicalSuru,
+    /// 文語ラ行変格
+    ClassicalIrregRu,
+    /// 文語ナ行変格
+    ClassicalIrregNu,
+    /// 文語形容詞-ク
+    ClassicalAdjectiveKu,
+    /// 文語形容詞-シク
+    ClassicalAdjectiveShiku,
+    /// 文語助動詞, holding the name of the auxilary like `ベシ` or `ナリ-断定`
+    ClassicalAuxilary(&'a str),
+    Unknown(&'a str),
+}
+
+impl<'a> ConjungationKind<'a> {
+    /// Parses the conjungation kind from the features, keeping unmapped values as
+    /// [`ConjungationKind::Unknown`]
+    pub fn from_features_lenient(value: &[&'a str]) -> Self {
+        Self::from_str_lenient(str_or_empty(value, FeatureColumn::CType.index()))
+    }
+
+    /// Parses a raw cType like `五段-カ行`, keeping unmapped values as
+    /// [`ConjungationKind::Unknown`]
+    pub fn from_str_lenient(raw: &'a str) -> Self {
+        let (kind, sub) = raw
+            .split_once('-')
+            .or_else(|| raw.split_once('・'))
+            .unwrap_or((raw, ""));
+        let row = || SyllableRow::from_str_lenient(sub);
+
+        match kind {
+            "*" => Self::None,
+            "五段" => Self::Godan(row()),
+            "上一段" => Self::KamiIchidan(row()),
+            "下一段" => Self::ShimoIchidan(row()),
+            "一段" => Self::Ichidan(row()),
+            "カ行変格" => Self::Kuru,
+            "サ行変格" => Self::Suru,
+            "ザ行変格" => Self::Zuru,
+            "ラ行変格" => Self::IrregRu,
+            "ナ行変格" => Self::IrregNu,
+            "形容詞" => Self::Adjective,
+            "助動詞" if !sub.is_empty() => Self::Auxilary(sub),
+            "無変化型" => Self::Invariant,
+            "文語四段" => Self::ClassicalYodan(row()),
+            "文語上一段" => Self::ClassicalKamiIchidan(row()),
+            "文語上二段" | "上二段" => Self::ClassicalKamiNidan(row()),
+            "文語下一段" => Self::ClassicalShimoIchidan(row()),
+            "文語下二段" => Self::ClassicalShimoNidan(row()),
+            "文語カ行変格" => Self::ClassicalKuru,
+            "文語サ行変格" => Self::ClassicalSuru,
+            "文語ラ行変格" => Self::ClassicalIrregRu,
+            "文語ナ行変格" => Self::ClassicalIrregNu,
+            "文語形容詞" if sub == "ク" => Self::ClassicalAdjectiveKu,
+            "文語形容詞" if sub == "シク" => Self::ClassicalAdjectiveShiku,
+            "文語助動詞" if !sub.is_empty() => Self::ClassicalAuxilary(sub),
+            _ => Self::Unknown(raw),
+        }
+    }
+
+    /// Returns the syllable row of the conjungation kind, if it has one
+    pub fn row(&self) -> Option<SyllableRow<'a>> {
+        match self {
+            Self::Godan(row)
+            | Self::KamiIchidan(row)
+            | Self::ShimoIchidan(row)
+            | Self::Ichidan(row)
+            | Self::ClassicalYodan(row)
+            | Self::ClassicalKamiIchidan(row)
+            | Self::ClassicalKamiNidan(row)
+            | Self::ClassicalShimoIchidan(row)
+            | Self::ClassicalShimoNidan(row) => Some(*row),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the conjungation kind belongs to classical Japanese (文語)
+    pub fn is_classical(&self) -> bool {
+        matches!(
+            self,
+            Self::ClassicalYodan(..)
+                | Self::ClassicalKamiIchidan(..)
+                | Self::ClassicalKamiNidan(..)
+                | Self::ClassicalShimoIchidan(..)
+                | Self::ClassicalShimoNidan(..)
+                | Self::ClassicalKuru
+                | Self::ClassicalSuru
+                | Self::ClassicalIrregRu
+                | Self::ClassicalIrregNu
+                | Self::ClassicalAdjectiveKu
+                | Self::ClassicalAdjectiveShiku
+                | Self::ClassicalAuxilary(..)
+        )
+    }
+
+    /// Returns the raw value if the kind or its syllable row is `Unknown`
+    pub fn unknown(&self) -> Option<(FeatureColumn, &'a str)> {
+        match self {
+            Self::Unknown(raw) => Some((FeatureColumn::CType, raw)),
+            _ => self.row().and_then(|row| row.unknown()),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Copy)]
-pub enum ConjungationForm {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConjungationForm<'a> {
     None,
     Plain,
     Imperative,
@@ -98,23 +517,15 @@ pub enum ConjungationForm {
     Stem,
     Realis,
     Kugohou,
+    Unknown(&'a str),
 }
 
-impl<'a> TryFrom<&Vec<&'a str>> for ConjungationKind {
-    type Error = String;
-    fn try_from(value: &Vec<&'a str>) -> Result<Self, Self::Error> {
-        Ok(match value[4] {
-            _ => Self::None,
-            //_ => return Err(format!("conjungation kind not found {}", value[4])),
-        })
-    }
-}
-
-impl<'a> TryFrom<&Vec<&'a str>> for ConjungationForm {
-    type Error = String;
-    fn try_from(value: &Vec<&'a str>) -> Result<Self, Self::Error> {
-        let t = split_type(value[5]).0;
-        Ok(match t {
+impl<'a> ConjungationForm<'a> {
+    /// Parses the conjungation form from the features, keeping unmapped values as
+    /// [`ConjungationForm::Unknown`]
+    pub fn from_features_lenient(value: &[&'a str]) -> Self {
+        let raw = str_or_empty(value, FeatureColumn::CForm.index());
+        match split_type(raw).0 {
             "*" => ConjungationForm::None,
             "終止形" => ConjungationForm::Plain,
             "命令形" => ConjungationForm::Imperative,
@@ -126,18 +537,47 @@ impl<'a> TryFrom<&Vec<&'a str>> for ConjungationForm {
             "已然形" => ConjungationForm::Realis,
             "意志推量形" => ConjungationForm::None, // wtf is this?
             "ク語法" => ConjungationForm::Kugohou,
-            _ => return Err(format!("conjungation form not found {}", t)),
-        })
+            _ => ConjungationForm::Unknown(raw),
+        }
+    }
+
+    /// Returns the raw value if the form is [`ConjungationForm::Unknown`]
+    pub fn unknown(&self) -> Option<(FeatureColumn, &'a str)> {
+        match self {
+            Self::Unknown(raw) => Some((FeatureColumn::CForm, raw)),
+            _ => None,
+        }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Copy)]
+impl<'a> TryFrom<&Vec<&'a str>> for ConjungationKind<'a> {
+    type Error = FeatureError;
+    fn try_from(value: &Vec<&'a str>) -> Result<Self, Self::Error> {
+        column(value, FeatureColumn::CType)?;
+        strict(value, Self::from_features_lenient(value), Self::unknown)
+    }
+}
+
+impl<'a> TryFrom<&Vec<&'a str>> for ConjungationForm<'a> {
+    type Error = FeatureError;
+    fn try_from(value: &Vec<&'a str>) -> Result<Self, Self::Error> {
+        strict(value, Self::from_features_lenient(value), Self::unknown)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WordClass<'a> {
-    Particle(ParticleType),
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Particle(ParticleType<'a>),
+    #[cfg_attr(feature = "serde", serde(borrow))]
     Verb(VerbType<'a>),
-    Adjective(AdjectiveType),
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Adjective(AdjectiveType<'a>),
+    #[default]
     Adverb,
-    Noun(NounType),
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Noun(NounType<'a>),
     Pronoun,
     Interjection,
     Symbol,
@@ -146,15 +586,15 @@ pub enum WordClass<'a> {
     Prefix,
     PreNoun,
     Space,
+    Unknown(&'a str),
 }
 
-impl<'a> Default for WordClass<'a> {
-    fn default() -> Self {
-        Self::Adverb
+impl<'a> WordClass<'a> {
+    /// Returns an owned copy of the word class
+    pub fn to_buf(&self) -> WordClassBuf {
+        (*self).into()
     }
-}
 
-impl<'a> WordClass<'a> {
     /// Returns `true` if the word_class is [`Particle`].
     pub fn is_particle(&self) -> bool {
         matches!(self, Self::Particle(..))
@@ -219,15 +659,21 @@ impl<'a> WordClass<'a> {
     pub fn is_adverb(&self) -> bool {
         matches!(self, Self::Adverb)
     }
-}
 
-impl<'a> TryFrom<&Vec<&'a str>> for WordClass<'a> {
-    type Error = String;
-    fn try_from(value: &Vec<&'a str>) -> Result<Self, Self::Error> {
-        Ok(match value[0] {
-            "助詞" => WordClass::Particle(ParticleType::try_from(value)?),
-            "形容詞" | "形状詞" => WordClass::Adjective(AdjectiveType::try_from(value)?),
-            "助動詞" | "動詞" => WordClass::Verb(VerbType::try_from(value)?),
+    /// Returns `true` if the word_class is [`Unknown`].
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown(..))
+    }
+
+    /// Parses the word class from the features, keeping unmapped values as `Unknown`
+    pub fn from_features_lenient(value: &[&'a str]) -> Self {
+        let pos1 = str_or_empty(value, FeatureColumn::Pos1.index());
+        match pos1 {
+            "助詞" => WordClass::Particle(ParticleType::from_features_lenient(value)),
+            "形容詞" | "形状詞" => {
+                WordClass::Adjective(AdjectiveType::from_features_lenient(value))
+            }
+            "助動詞" | "動詞" => WordClass::Verb(VerbType::from_features_lenient(value)),
             "代名詞" => WordClass::Pronoun,
             "感動詞" => WordClass::Interjection,
             "補助記号" | "記号" => WordClass::Symbol,
@@ -236,10 +682,30 @@ impl<'a> TryFrom<&Vec<&'a str>> for WordClass<'a> {
             "接頭辞" => WordClass::Prefix,
             "副詞" => WordClass::Adverb,
             "空白" => WordClass::Space,
-            "名詞" => WordClass::Noun(NounType::try_from(value)?),
+            "名詞" => WordClass::Noun(NounType::from_features_lenient(value)),
             "連体詞" => WordClass::PreNoun,
-            _ => return Err(format!("wc not found {}", value[0])),
-        })
+            _ => WordClass::Unknown(pos1),
+        }
+    }
+
+    /// Returns the first feature value which was kept as `Unknown`, including the ones of the
+    /// nested types
+    pub fn unknown(&self) -> Option<(FeatureColumn, &'a str)> {
+        match self {
+            Self::Particle(p) => p.unknown(),
+            Self::Verb(v) => v.unknown(),
+            Self::Adjective(a) => a.unknown(),
+            Self::Noun(n) => n.unknown(),
+            Self::Unknown(raw) => Some((FeatureColumn::Pos1, raw)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> TryFrom<&Vec<&'a str>> for WordClass<'a> {
+    type Error = FeatureError;
+    fn try_from(value: &Vec<&'a str>) -> Result<Self, Self::Error> {
+        strict(value, Self::from_features_lenient(value), Self::unknown)
     }
 }
 
@@ -247,25 +713,43 @@ impl<'a> TryFrom<&Vec<&'a str>> for WordClass<'a> {
 // ------ Noun
 //
 #[derive(Clone, Debug, PartialEq, Copy)]
-pub enum NounType {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NounType<'a> {
     Common,
     Proper,
     Numeral,
     Suffix,
     Jodoushi,
+    Unknown(&'a str),
 }
 
-impl TryFrom<&Vec<&str>> for NounType {
-    type Error = String;
-    fn try_from(value: &Vec<&str>) -> Result<Self, Self::Error> {
-        Ok(match value[1] {
+impl<'a> NounType<'a> {
+    /// Parses the noun type from the features, keeping unmapped values as `Unknown`
+    pub fn from_features_lenient(value: &[&'a str]) -> Self {
+        let pos2 = str_or_empty(value, FeatureColumn::Pos2.index());
+        match pos2 {
             "普通名詞" => Self::Common,
             "固有名詞" => Self::Proper,
             "数詞" | "数" => Self::Numeral,
             "接尾" => Self::Suffix,
             "助動詞語幹" => Self::Jodoushi,
-            _ => return Err(format!("Nountype not found {}", value[1])),
-        })
+            _ => Self::Unknown(pos2),
+        }
+    }
+
+    /// Returns the raw value if the noun type is `Unknown`
+    pub fn unknown(&self) -> Option<(FeatureColumn, &'a str)> {
+        match self {
+            Self::Unknown(raw) => Some((FeatureColumn::Pos2, raw)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> TryFrom<&Vec<&'a str>> for NounType<'a> {
+    type Error = FeatureError;
+    fn try_from(value: &Vec<&'a str>) -> Result<Self, Self::Error> {
+        strict(value, Self::from_features_lenient(value), Self::unknown)
     }
 }
 
@@ -273,27 +757,45 @@ impl TryFrom<&Vec<&str>> for NounType {
 // ------ Particle
 //
 #[derive(Clone, Debug, PartialEq, Copy)]
-pub enum ParticleType {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParticleType<'a> {
     Connecting,
     SentenceEnding,
     CaseMaking,
     Conjungtion,
     Adverbial,
     Nominalizing,
+    Unknown(&'a str),
 }
 
-impl TryFrom<&Vec<&str>> for ParticleType {
-    type Error = String;
-    fn try_from(value: &Vec<&str>) -> Result<Self, Self::Error> {
-        Ok(match value[1] {
+impl<'a> ParticleType<'a> {
+    /// Parses the particle type from the features, keeping unmapped values as `Unknown`
+    pub fn from_features_lenient(value: &[&'a str]) -> Self {
+        let pos2 = str_or_empty(value, FeatureColumn::Pos2.index());
+        match pos2 {
             "係助詞" => Self::Connecting,
             "終助詞" => Self::SentenceEnding,
             "格助詞" => Self::CaseMaking,
             "接続助詞" => Self::Conjungtion,
             "副助詞" => Self::Adverbial,
             "準体助詞" => Self::Nominalizing,
-            _ => return Err(format!("particle not found {}", value[1])),
-        })
+            _ => Self::Unknown(pos2),
+        }
+    }
+
+    /// Returns the raw value if the particle type is `Unknown`
+    pub fn unknown(&self) -> Option<(FeatureColumn, &'a str)> {
+        match self {
+            Self::Unknown(raw) => Some((FeatureColumn::Pos2, raw)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> TryFrom<&Vec<&'a str>> for ParticleType<'a> {
+    type Error = FeatureError;
+    fn try_from(value: &Vec<&'a str>) -> Result<Self, Self::Error> {
+        strict(value, Self::from_features_lenient(value), Self::unknown)
     }
 }
 
@@ -301,19 +803,37 @@ impl TryFrom<&Vec<&str>> for ParticleType {
 // ------ Adjective
 //
 #[derive(Clone, Debug, PartialEq, Copy)]
-pub enum AdjectiveType {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AdjectiveType<'a> {
     I,
     Na,
+    Unknown(&'a str),
 }
 
-impl TryFrom<&Vec<&str>> for AdjectiveType {
-    type Error = String;
-    fn try_from(value: &Vec<&str>) -> Result<Self, Self::Error> {
-        Ok(match value[0] {
+impl<'a> AdjectiveType<'a> {
+    /// Parses the adjective type from the features, keeping unmapped values as `Unknown`
+    pub fn from_features_lenient(value: &[&'a str]) -> Self {
+        let pos1 = str_or_empty(value, FeatureColumn::Pos1.index());
+        match pos1 {
             "形容詞" => Self::I,
             "形状詞" => Self::Na,
-            _ => return Err(format!("adjective not found {}", value[0])),
-        })
+            _ => Self::Unknown(pos1),
+        }
+    }
+
+    /// Returns the raw value if the adjective type is `Unknown`
+    pub fn unknown(&self) -> Option<(FeatureColumn, &'a str)> {
+        match self {
+            Self::Unknown(raw) => Some((FeatureColumn::Pos1, raw)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> TryFrom<&Vec<&'a str>> for AdjectiveType<'a> {
+    type Error = FeatureError;
+    fn try_from(value: &Vec<&'a str>) -> Result<Self, Self::Error> {
+        strict(value, Self::from_features_lenient(value), Self::unknown)
     }
 }
 
@@ -321,49 +841,80 @@ impl TryFrom<&Vec<&str>> for AdjectiveType {
 // ------ Verb
 //
 #[derive(Clone, Debug, PartialEq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VerbType<'a> {
     Auxilary(&'a str),
-    Godan(SyllableRow),
-    Ichidan(SyllableRow),
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Godan(SyllableRow<'a>),
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Ichidan(SyllableRow<'a>),
     IchidanEruConjungation,
     IrrWrittenLang,
     Suru,
     Kuru,
+    /// ザ行変格, like 感ずる
+    Zuru,
     IrregRu,
     IrregNu,
+    Unknown(&'a str),
 }
 
 impl<'a> TryFrom<&Vec<&'a str>> for VerbType<'a> {
-    type Error = String;
+    type Error = FeatureError;
     fn try_from(value: &Vec<&'a str>) -> Result<Self, Self::Error> {
-        let verb_type = value[4];
+        let pos1 = column(value, FeatureColumn::Pos1)?;
+        if pos1 != "助動詞" && pos1 != "動詞" {
+            return Err(FeatureError::unknown(FeatureColumn::Pos1, pos1));
+        }
 
-        Ok(match value[0] {
-            "助動詞" => Self::Auxilary(split_type(verb_type).1),
-            "動詞" => Self::parse_general(verb_type)?,
-            _ => return Err(format!("Verb not found {}", value[0])),
-        })
+        strict(value, Self::from_features_lenient(value), Self::unknown)
     }
 }
 
 impl<'a> VerbType<'a> {
-    fn parse_general(verb_type: &'a str) -> Result<Self, String> {
-        let verb_type = split_type(verb_type);
-        Ok(match verb_type.0 {
-            "五段" | "文語下二段" | "文語四段" | "文語上二段" | "上二段" => {
-                VerbType::Godan(SyllableRow::try_from(verb_type.1)?)
-            }
-            "一段" | "下一段" | "上一段" => {
-                VerbType::Ichidan(SyllableRow::try_from(verb_type.1)?)
-            }
-            "サ行変格" | "文語サ行変格" => VerbType::Suru,
-            "カ行変格" => VerbType::Kuru,
-            "ラ行変格" | "文語ラ行変格" => VerbType::IrregRu,
-            "ナ行変格" | "文語ナ行変格" => VerbType::IrregNu,
-            "文語カ行変格" => VerbType::IrrWrittenLang,
-            "文語上一段" => VerbType::IrrWrittenLang,
-            _ => return Err(format!("Verbtype {} not found", verb_type.0)),
-        })
+    /// Returns an owned copy of the verb type
+    pub fn to_buf(&self) -> VerbTypeBuf {
+        (*self).into()
+    }
+
+    /// Parses the verb type from the features, keeping unmapped values as `Unknown`
+    pub fn from_features_lenient(value: &[&'a str]) -> Self {
+        let verb_type = str_or_empty(value, FeatureColumn::CType.index());
+
+        match str_or_empty(value, FeatureColumn::Pos1.index()) {
+            "助動詞" => Self::Auxilary(split_type(verb_type).1),
+            _ => Self::parse_general(verb_type),
+        }
+    }
+
+    /// Returns the raw value if the verb type or its syllable row is `Unknown`
+    pub fn unknown(&self) -> Option<(FeatureColumn, &'a str)> {
+        match self {
+            Self::Godan(row) | Self::Ichidan(row) => row.unknown(),
+            Self::Unknown(raw) => Some((FeatureColumn::CType, raw)),
+            _ => None,
+        }
+    }
+
+    fn parse_general(raw: &'a str) -> Self {
+        match ConjungationKind::from_str_lenient(raw) {
+            ConjungationKind::Godan(row)
+            | ConjungationKind::ClassicalShimoNidan(row)
+            | ConjungationKind::ClassicalYodan(row)
+            | ConjungationKind::ClassicalKamiNidan(row) => VerbType::Godan(row),
+            ConjungationKind::Ichidan(row)
+            | ConjungationKind::ShimoIchidan(row)
+            | ConjungationKind::KamiIchidan(row) => VerbType::Ichidan(row),
+            ConjungationKind::Suru | ConjungationKind::ClassicalSuru => VerbType::Suru,
+            ConjungationKind::Kuru => VerbType::Kuru,
+            ConjungationKind::Zuru => VerbType::Zuru,
+            ConjungationKind::IrregRu | ConjungationKind::ClassicalIrregRu => VerbType::IrregRu,
+            ConjungationKind::IrregNu | ConjungationKind::ClassicalIrregNu => VerbType::IrregNu,
+            ConjungationKind::ClassicalKuru
+            | ConjungationKind::ClassicalKamiIchidan(_)
+            | ConjungationKind::ClassicalShimoIchidan(_) => VerbType::IrrWrittenLang,
+            _ => VerbType::Unknown(raw),
+        }
     }
 }
 
@@ -372,7 +923,8 @@ impl<'a> VerbType<'a> {
 //
 
 #[derive(Clone, Debug, PartialEq, Copy)]
-pub enum SyllableRow {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SyllableRow<'a> {
     G,
     K,
     M,
@@ -388,12 +940,13 @@ pub enum SyllableRow {
     N,
     Wa,
     Y,
+    Unknown(&'a str),
 }
 
-impl<'a> TryFrom<&'a str> for SyllableRow {
-    type Error = String;
-    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
-        Ok(match value {
+impl<'a> SyllableRow<'a> {
+    /// Parses a syllable row like `カ行`, keeping unmapped values as `Unknown`
+    pub fn from_str_lenient(value: &'a str) -> Self {
+        match value {
             "ガ行" => SyllableRow::G,
             "カ行" => SyllableRow::K,
             "マ行" => SyllableRow::M,
@@ -407,14 +960,33 @@ impl<'a> TryFrom<&'a str> for SyllableRow {
             "バ行" => SyllableRow::B,
             "パ行" => SyllableRow::P,
             "ナ行" => SyllableRow::N,
-            "ワア行" => SyllableRow::Wa,
+            "ワア行" | "ワ行" => SyllableRow::Wa,
             "ヤ行" => SyllableRow::Y,
-            _ => return Err(format!("Syllable ending not found {}", value)),
-        })
+            _ => SyllableRow::Unknown(value),
+        }
+    }
+
+    /// Returns the raw value if the syllable row is `Unknown`
+    pub fn unknown(&self) -> Option<(FeatureColumn, &'a str)> {
+        match self {
+            Self::Unknown(raw) => Some((FeatureColumn::CType, raw)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for SyllableRow<'a> {
+    type Error = FeatureError;
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        match Self::from_str_lenient(value) {
+            Self::Unknown(raw) => Err(FeatureError::unknown(FeatureColumn::CType, raw)),
+            row => Ok(row),
+        }
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Origin {
     China,
     Japan,
@@ -434,8 +1006,42 @@ impl Origin {
 // Helper
 //
 
+/// Returns the value of `column` or an error if the feature string is too short
+fn column<'a>(features: &[&'a str], column: FeatureColumn) -> Result<&'a str, FeatureError> {
+    features
+        .get(column.index())
+        .copied()
+        .ok_or(FeatureError::MissingColumn { column })
+}
+
+/// Turns a leniently parsed value into an error if it kept an `Unknown` value
+fn strict<'a, T>(
+    features: &[&'a str],
+    value: T,
+    unknown: impl Fn(&T) -> Option<(FeatureColumn, &'a str)>,
+) -> Result<T, FeatureError> {
+    match unknown(&value) {
+        Some(unknown) => Err(unknown_error(features, unknown)),
+        None => Ok(value),
+    }
+}
+
+/// Builds the error for an `Unknown` value, reporting the whole raw column
+fn unknown_error(features: &[&str], (column, _): (FeatureColumn, &str)) -> FeatureError {
+    match features.get(column.index()) {
+        Some(raw) => FeatureError::unknown(column, raw),
+        None => FeatureError::MissingColumn { column },
+    }
+}
+
+/// Returns the byte offset of `part` within `text` if `part` is a subslice of `text`
+fn byte_offset(text: &str, part: &str) -> Option<usize> {
+    let start = (part.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+    (start + part.len() <= text.len()).then_some(start)
+}
+
 /// Splits a type definition and gets both sides
-fn split_type<'a>(inp: &'a str) -> (&'a str, &'a str) {
+fn split_type(inp: &str) -> (&str, &str) {
     let mut s = if inp.contains("-") {
         inp.split("-")
     } else {
@@ -444,10 +1050,154 @@ fn split_type<'a>(inp: &'a str) -> (&'a str, &'a str) {
     (s.next().unwrap_or(""), s.next().unwrap_or(""))
 }
 
-fn str_or_empty<'a>(vec: &Vec<&'a str>, pos: usize) -> &'a str {
+fn str_or_empty<'a>(vec: &[&'a str], pos: usize) -> &'a str {
     if vec.len() > pos {
         vec[pos]
     } else {
         ""
     }
 }
+
+/// Creates a morpheme of `surface` at `start` from the feature string `feature`
+#[cfg(test)]
+pub(crate) fn morph<'dict, 'input>(
+    surface: &'input str,
+    start: usize,
+    feature: &'dict str,
+) -> Morpheme<'dict, 'input> {
+    Morpheme::from_raw_lenient(RawMorpheme {
+        surface,
+        feature,
+        start,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(feature: &str) -> RawMorpheme<'_, '_> {
+        RawMorpheme {
+            surface: "東京",
+            feature,
+            start: 0,
+        }
+    }
+
+    #[test]
+    fn strict_parsing_reports_missing_c_type() {
+        let err = Morpheme::try_from_raw(raw("名詞,固有名詞,地名,一般")).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::MissingColumn {
+                column: FeatureColumn::CType,
+                ..
+            }
+        ));
+
+        let features = vec!["名詞", "固有名詞", "地名", "一般"];
+        let err = ConjungationKind::try_from(&features).unwrap_err();
+        assert!(matches!(
+            err,
+            FeatureError::MissingColumn {
+                column: FeatureColumn::CType
+            }
+        ));
+    }
+
+    #[test]
+    fn classical_irregular_kinds_are_classical() {
+        for (raw, kind) in [
+            ("文語ラ行変格", ConjungationKind::ClassicalIrregRu),
+            ("文語ナ行変格", ConjungationKind::ClassicalIrregNu),
+            ("文語カ行変格", ConjungationKind::ClassicalKuru),
+        ] {
+            assert_eq!(ConjungationKind::from_str_lenient(raw), kind);
+            assert!(kind.is_classical());
+        }
+
+        assert!(!ConjungationKind::from_str_lenient("ラ行変格").is_classical());
+        assert!(!ConjungationKind::from_str_lenient("ナ行変格").is_classical());
+    }
+
+    #[test]
+    fn strict_parsing_maps_zuru_and_classical_shimo_ichidan() {
+        let feature =
+            "動詞,一般,*,*,ザ行変格,終止形-一般,カンズル,感ずる,感ずる,カンズル,感ずる,カンズル,漢";
+        let morph = Morpheme::try_from_raw(raw(feature)).unwrap();
+        assert_eq!(morph.word_class, WordClass::Verb(VerbType::Zuru));
+        assert_eq!(morph.conjungation.kind, ConjungationKind::Zuru);
+
+        let feature = "動詞,一般,*,*,文語下一段-カ行,終止形-一般,ケル,蹴る,蹴る,ケル,蹴る,ケル,和";
+        let morph = Morpheme::try_from_raw(raw(feature)).unwrap();
+        assert_eq!(morph.word_class, WordClass::Verb(VerbType::IrrWrittenLang));
+    }
+
+    #[test]
+    fn lenient_parsing_keeps_unknown_values() {
+        let morph = Morpheme::from_raw_lenient(raw("謎,*,*,*,*,*"));
+        assert_eq!(morph.word_class, WordClass::Unknown("謎"));
+        assert_eq!(morph.unknown_features(), vec![(FeatureColumn::Pos1, "謎")]);
+        assert!(Morpheme::try_from_raw(raw("謎,*,*,*,*,*")).is_err());
+    }
+
+    /// Returns the files of the fixture dictionary, which live as long as the tests
+    fn fixture_bytes() -> DictionaryBytes {
+        use std::sync::OnceLock;
+
+        static FILES: OnceLock<Vec<Vec<u8>>> = OnceLock::new();
+        let files = FILES.get_or_init(|| {
+            DICTIONARY_FILES
+                .iter()
+                .map(|name| fs::read(dictionary::fixture().join(name)).unwrap())
+                .collect()
+        });
+        DictionaryBytes {
+            word2id: &files[0],
+            word_dat: &files[1],
+            word_ary_idx: &files[2],
+            word_inf: &files[3],
+            matrix_bin: &files[4],
+            char_category: &files[5],
+            code2category: &files[6],
+        }
+    }
+
+    fn surfaces(parser: &Parser, text: &str) -> Vec<String> {
+        parser
+            .parse(text)
+            .iter()
+            .map(|morph| morph.surface.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn dictionaries_from_bytes_and_mmap() {
+        let path = dictionary::fixture().to_str().unwrap();
+        for parser in [
+            Parser::from_bytes(fixture_bytes()).unwrap(),
+            Parser::from_mmap(path).unwrap(),
+        ] {
+            assert!(parser.parser.is_none());
+            assert_eq!(surfaces(&parser, "東京都庁に"), vec!["東京都", "庁", "に"]);
+            assert_eq!(parser.parse("東京都庁に")[2].start, 12);
+            assert_eq!(parser.parse_nbest("東京都庁に", 1)[0].cost, 6700);
+        }
+    }
+
+    #[test]
+    fn corrupt_bytes_are_rejected() {
+        let bytes = DictionaryBytes {
+            matrix_bin: &[0; 7],
+            ..fixture_bytes()
+        };
+        assert!(matches!(
+            Parser::from_bytes(bytes),
+            Err(DictionaryError::CorruptBytes("matrix.bin"))
+        ));
+        assert!(matches!(
+            Parser::from_mmap("/nonexistent/dictionary"),
+            Err(DictionaryError::NotADirectory(_))
+        ));
+    }
+}
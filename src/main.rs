@@ -0,0 +1,357 @@
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    process,
+};
+
+use igo_unidic::{
+    compile_dictionary, CompileProgress, Morpheme, NormalizeOptions, Parser, UnidicFeatures,
+    UserDictionary,
+};
+
+/// Environment variable holding the dictionary path if `--dict` is not given
+const DICT_ENV: &str = "IGO_UNIDIC_DICT";
+
+const USAGE: &str = "Usage: main [OPTIONS] [FILE]...
+       main build <SOURCE DIR> <OUTPUT DIR>
+
+Analyzes the given files, or stdin if none are given, and prints one morpheme per line.
+`build` compiles the lex.csv, matrix.def, char.def and unk.def of UniDic in SOURCE DIR into a
+dictionary in OUTPUT DIR.
+
+Options:
+  -d, --dict <PATH>        Dictionary directory (default: $IGO_UNIDIC_DICT)
+  -u, --user-dict <FILE>   User dictionary CSV, in UniDic or surface,reading,word class layout
+  -n, --normalize          Normalize the input by NFKC, voiced sound marks and dashes before parsing
+  -f, --format <FORMAT>    Output format: tsv, mecab or wakati (default: tsv)
+  -s, --strict             Fail on features which can't be mapped instead of printing them as Unknown
+  -h, --help               Print this help";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    /// surface, part of speech, conjungation type, conjungation form, basic form and reading,
+    /// separated by tabs. The part of speech and conjungation are the UniDic column values, with
+    /// the levels of the part of speech joined by `-` like 名詞-固有名詞-地名
+    Tsv,
+    /// surface and the raw feature string, like mecab prints it
+    Mecab,
+    /// surfaces separated by spaces, one input line per output line
+    Wakati,
+}
+
+impl Format {
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "tsv" => Self::Tsv,
+            "mecab" => Self::Mecab,
+            "wakati" => Self::Wakati,
+            _ => return None,
+        })
+    }
+}
+
+struct Options {
+    dict: String,
+    user_dict: Option<String>,
+    normalize: bool,
+    format: Format,
+    strict: bool,
+    files: Vec<String>,
+}
+
+fn main() {
+    let mut args = env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("build") {
+        args.next();
+        build(args);
+        return;
+    }
+
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let mut parser = match Parser::new(&options.dict) {
+        Ok(parser) => parser,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+
+    if let Some(path) = &options.user_dict {
+        let loaded =
+            UserDictionary::load(path).and_then(|user_dict| parser.with_user_dictionary(user_dict));
+        parser = match loaded {
+            Ok(parser) => parser,
+            Err(err) => {
+                eprintln!("{}: {}", path, err);
+                process::exit(1);
+            }
+        };
+    }
+
+    if options.normalize {
+        parser = parser.with_normalization(NormalizeOptions::default());
+    }
+
+    if let Err(err) = run(&parser, &options) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+/// Compiles a dictionary, printing the progress to stderr
+fn build(args: impl Iterator<Item = String>) {
+    let args: Vec<String> = args.collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        process::exit(0);
+    }
+    let (source, output) = match args.as_slice() {
+        [source, output] => (source, output),
+        _ => {
+            eprintln!(
+                "build expects a source and an output directory\n\n{}",
+                USAGE
+            );
+            process::exit(2);
+        }
+    };
+
+    let result = compile_dictionary(source, output, |progress| match progress {
+        CompileProgress::Reading { file, lines } => {
+            eprintln!("reading {}: {} lines", file.display(), lines)
+        }
+        CompileProgress::BuildingTrie { keys } => eprintln!("building trie of {} keys", keys),
+        CompileProgress::Written { file, bytes } => {
+            eprintln!("wrote {} ({} bytes)", file.display(), bytes)
+        }
+    });
+
+    match result {
+        Ok(summary) => eprintln!(
+            "compiled {} words, {} keys, {} categories and {}x{} connection costs",
+            summary.words, summary.keys, summary.categories, summary.right_ids, summary.left_ids
+        ),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut dict = env::var(DICT_ENV).ok();
+    let mut user_dict = None;
+    let mut normalize = false;
+    let mut format = Format::Tsv;
+    let mut strict = false;
+    let mut files = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dict" => {
+                dict = Some(args.next().ok_or("missing value for --dict")?);
+            }
+            "-u" | "--user-dict" => {
+                user_dict = Some(args.next().ok_or("missing value for --user-dict")?);
+            }
+            "-n" | "--normalize" => normalize = true,
+            "-f" | "--format" => {
+                let value = args.next().ok_or("missing value for --format")?;
+                format = Format::parse(&value).ok_or(format!("unknown format {:?}", value))?;
+            }
+            "-s" | "--strict" => strict = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "-" => files.push(arg),
+            _ if arg.starts_with('-') => return Err(format!("unknown option {:?}", arg)),
+            _ => files.push(arg),
+        }
+    }
+
+    let dict = dict.ok_or(format!(
+        "no dictionary given, use --dict or set {}",
+        DICT_ENV
+    ))?;
+
+    Ok(Options {
+        dict,
+        user_dict,
+        normalize,
+        format,
+        strict,
+        files,
+    })
+}
+
+fn run(parser: &Parser, options: &Options) -> Result<(), String> {
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+
+    if options.files.is_empty() {
+        let stdin = io::stdin();
+        return analyze(parser, options, stdin.lock(), &mut out);
+    }
+
+    for path in &options.files {
+        if path == "-" {
+            let stdin = io::stdin();
+            analyze(parser, options, stdin.lock(), &mut out)?;
+            continue;
+        }
+
+        let file = File::open(path).map_err(|err| format!("can't open {}: {}", path, err))?;
+        analyze(parser, options, BufReader::new(file), &mut out)?;
+    }
+
+    Ok(())
+}
+
+/// Analyzes `input` line by line and writes the result to `out`
+fn analyze(
+    parser: &Parser,
+    options: &Options,
+    input: impl BufRead,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    for line in input.lines() {
+        let line = line.map_err(|err| err.to_string())?;
+
+        let morphemes = if options.strict {
+            parser.try_parse(&line).map_err(|err| err.to_string())?
+        } else {
+            parser.parse_lenient(&line)
+        };
+
+        print_morphemes(&morphemes, options.format, out).map_err(|err| err.to_string())?;
+    }
+
+    out.flush().map_err(|err| err.to_string())
+}
+
+/// Joins the levels of the part of speech which are set with `-`
+fn part_of_speech(features: &UnidicFeatures) -> String {
+    let levels = [features.pos1, features.pos2, features.pos3, features.pos4];
+    let levels: Vec<_> = levels
+        .iter()
+        .copied()
+        .filter(|level| !level.is_empty() && *level != "*")
+        .collect();
+    levels.join("-")
+}
+
+fn print_morphemes(morphemes: &[Morpheme], format: Format, out: &mut impl Write) -> io::Result<()> {
+    match format {
+        Format::Tsv => {
+            for morph in morphemes {
+                let features = morph.features();
+                writeln!(
+                    out,
+                    "{}\t{}\t{}\t{}\t{}\t{}",
+                    morph.surface,
+                    part_of_speech(&features),
+                    features.c_type,
+                    features.c_form,
+                    morph.basic,
+                    morph.reading
+                )?;
+            }
+            writeln!(out)
+        }
+        Format::Mecab => {
+            for morph in morphemes {
+                writeln!(out, "{}\t{}", morph.surface, morph.feature)?;
+            }
+            writeln!(out, "EOS")
+        }
+        Format::Wakati => {
+            let surfaces: Vec<_> = morphemes.iter().map(|m| m.surface).collect();
+            writeln!(out, "{}", surfaces.join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn options_and_files() {
+        let options = parse(&[
+            "-d",
+            "dict",
+            "--user-dict",
+            "user.csv",
+            "-n",
+            "-s",
+            "--format",
+            "mecab",
+            "a.txt",
+            "-",
+        ])
+        .unwrap();
+        assert_eq!(options.dict, "dict");
+        assert_eq!(options.user_dict.as_deref(), Some("user.csv"));
+        assert!(options.normalize);
+        assert!(options.strict);
+        assert_eq!(options.format, Format::Mecab);
+        assert_eq!(options.files, vec!["a.txt", "-"]);
+
+        let options = parse(&["--dict", "dict", "-f", "wakati"]).unwrap();
+        assert_eq!(options.user_dict, None);
+        assert!(!options.normalize && !options.strict);
+        assert_eq!(options.format, Format::Wakati);
+        assert!(options.files.is_empty());
+
+        assert_eq!(parse(&["-d", "dict"]).unwrap().format, Format::Tsv);
+        // The last value wins
+        assert_eq!(parse(&["-d", "a", "-d", "b"]).unwrap().dict, "b");
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert_eq!(
+            parse(&["-d", "dict", "-f", "json"]).err(),
+            Some("unknown format \"json\"".to_string())
+        );
+        assert_eq!(
+            parse(&["-d", "dict", "--verbose"]).err(),
+            Some("unknown option \"--verbose\"".to_string())
+        );
+        assert_eq!(
+            parse(&["-d"]).err(),
+            Some("missing value for --dict".to_string())
+        );
+        assert_eq!(
+            parse(&["-d", "dict", "-u"]).err(),
+            Some("missing value for --user-dict".to_string())
+        );
+        assert_eq!(
+            parse(&["-d", "dict", "--format"]).err(),
+            Some("missing value for --format".to_string())
+        );
+    }
+
+    #[test]
+    fn parts_of_speech() {
+        let features = UnidicFeatures::from_feature("名詞,固有名詞,地名,一般,*,*");
+        assert_eq!(part_of_speech(&features), "名詞-固有名詞-地名-一般");
+        let features = UnidicFeatures::from_feature("動詞,一般,*,*,五段-カ行,連用形-一般");
+        assert_eq!(part_of_speech(&features), "動詞-一般");
+        assert_eq!(features.c_type, "五段-カ行");
+        assert_eq!(part_of_speech(&UnidicFeatures::from_feature("")), "");
+    }
+}
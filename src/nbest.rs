@@ -0,0 +1,120 @@
+use crate::{lattice::Lattice, Morpheme, ParseError, Parser, RawMorpheme};
+
+/// One of the analyses of a text returned by [`Parser::parse_nbest`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analysis<'dict, 'text> {
+    pub morphemes: Vec<Morpheme<'dict, 'text>>,
+    /// Sum of the costs of the words and of the connections between them. Lower costs are more
+    /// likely analyses
+    pub cost: i32,
+}
+
+impl Parser {
+    /// Parses `text` into its `n` most likely analyses, cheapest first. The first analysis is the
+    /// cheapest path through the lattice, which is the one [`Parser::parse`] returns. Fewer
+    /// analyses are returned if the text can't be segmented in `n` different ways
+    pub fn parse_nbest<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+        n: usize,
+    ) -> Vec<Analysis<'dict, 'text>> {
+        self.try_parse_nbest(text, n).unwrap()
+    }
+
+    /// Same as [`Parser::parse_nbest`], returning an error for the first morpheme whose features
+    /// can't be mapped
+    pub fn try_parse_nbest<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+        n: usize,
+    ) -> Result<Vec<Analysis<'dict, 'text>>, ParseError> {
+        self.tag_nbest(text, n)
+            .into_iter()
+            .map(|(morphemes, cost)| {
+                Ok(Analysis {
+                    morphemes: morphemes
+                        .into_iter()
+                        .map(Morpheme::try_from_raw)
+                        .collect::<Result<_, _>>()?,
+                    cost,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the `n` cheapest paths through the lattice of `text` along with their costs
+    pub(crate) fn tag_paths<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+        n: usize,
+    ) -> Vec<(Vec<RawMorpheme<'dict, 'text>>, i32)> {
+        let lattice = Lattice::new(&self.dictionary, self.user_dictionary.as_deref(), text);
+        lattice
+            .best_paths(n)
+            .into_iter()
+            .map(|(path, cost)| (lattice.morphemes(text, &path), cost))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{dictionary::fixture, NormalizeOptions, Parser, UserDictionary};
+
+    fn parser() -> Parser {
+        Parser::new(fixture().to_str().unwrap()).unwrap()
+    }
+
+    fn surfaces(parser: &Parser, text: &str, n: usize) -> Vec<(Vec<String>, i32)> {
+        parser
+            .parse_nbest(text, n)
+            .into_iter()
+            .map(|analysis| {
+                let surfaces = analysis
+                    .morphemes
+                    .iter()
+                    .map(|morpheme| morpheme.surface.to_string())
+                    .collect();
+                (surfaces, analysis.cost)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn analyses_by_cost() {
+        let parser = parser();
+        assert_eq!(
+            surfaces(&parser, "東京都庁に", 2),
+            vec![
+                (vec!["東京都".into(), "庁".into(), "に".into()], 6700),
+                (
+                    vec!["東京".into(), "都".into(), "庁".into(), "に".into()],
+                    11200
+                ),
+            ]
+        );
+
+        let analyses = parser.parse_nbest("東京都庁に", 1);
+        assert_eq!(analyses[0].morphemes[2].start, 12);
+        assert!(parser.parse_nbest("東京都庁に", 0).is_empty());
+    }
+
+    #[test]
+    fn user_words_and_normalization() {
+        let mut dictionary = UserDictionary::new();
+        dictionary
+            .add_word("都庁", "トチョウ", "名詞-固有名詞", Some(1000))
+            .unwrap();
+        let parser = parser()
+            .with_user_dictionary(dictionary)
+            .unwrap()
+            .with_normalization(NormalizeOptions::default());
+
+        // The ideographic space becomes a space which is not part of any analysis
+        let analyses = parser.parse_nbest("東京都庁　に", 3);
+        let first: Vec<_> = analyses[0].morphemes.iter().map(|m| m.surface).collect();
+        assert_eq!(first, vec!["東京", "都庁", "に"]);
+        assert_eq!(analyses[0].morphemes[2].start, 15);
+        assert!(analyses.windows(2).all(|pair| pair[0].cost <= pair[1].cost));
+    }
+}
@@ -0,0 +1,346 @@
+use std::ops::Range;
+
+use unicode_normalization::{
+    char::{canonical_combining_class, compose},
+    UnicodeNormalization,
+};
+
+use crate::{
+    kana::{
+        full_width_to_ascii, half_to_full_width_kana, hiragana_to_katakana_char, is_hiragana,
+        is_kana, katakana_to_hiragana_char, semi_voiced, voiced,
+    },
+    Parser, RawMorpheme,
+};
+
+/// Rules applied to the input before parsing it. The Japanese specific rules run first, then
+/// the text is normalized by NFKC if [`NormalizeOptions::compatibility`] is set
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NormalizeOptions {
+    /// Applies unicode normalization form KC, which also replaces compatibility characters like
+    /// ①, ㍻, ㌔ or ﬁ by 1, 平成, キロ and fi. It covers most of the width and whitespace rules
+    pub compatibility: bool,
+    /// Converts full width ASCII and the ideographic space into ASCII and half width katakana
+    /// into full width, merging half width voiced sound marks like in ｶﾞ
+    pub width: bool,
+    /// Merges combining and spacing voiced sound marks into the kana before them, like か゛ or
+    /// か followed by U+3099 into が
+    pub combining_marks: bool,
+    /// Replaces no-break, ideographic and other unicode spaces as well as tabs by a space
+    pub whitespace: bool,
+    /// Replaces dashes and hyphens following a kana, like the － of ラ－メン, by ー
+    pub prolonged_sound_marks: bool,
+}
+
+impl Default for NormalizeOptions {
+    /// Enables all rules
+    fn default() -> Self {
+        NormalizeOptions {
+            compatibility: true,
+            width: true,
+            combining_marks: true,
+            whitespace: true,
+            prolonged_sound_marks: true,
+        }
+    }
+}
+
+/// A normalized text along with the mapping of its offsets to the original text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Normalized {
+    pub text: String,
+    /// Byte offsets of each part of `text` and of the original characters it was created from,
+    /// followed by the lengths of both texts. A part is a single character, or all characters
+    /// NFKC created from a character and the combining characters following it
+    offsets: Vec<(usize, usize)>,
+}
+
+impl Normalized {
+    /// Returns the byte offset within the original text of the byte offset `offset` within the
+    /// normalized text. Offsets within a part map to the start of its original characters
+    pub fn original_offset(&self, offset: usize) -> usize {
+        let i = self
+            .offsets
+            .partition_point(|(normalized, _)| *normalized <= offset);
+        self.offsets[i.saturating_sub(1)].1
+    }
+
+    /// Returns the byte range within the original text the byte range `range` within the
+    /// normalized text was created from. Ranges starting or ending within a part are widened to
+    /// all of it, so both halves of the 平成 created from ㍻ map to ㍻
+    pub fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        let i = self
+            .offsets
+            .partition_point(|(normalized, _)| *normalized < range.end);
+        let end = self
+            .offsets
+            .get(i)
+            .map_or(range.end, |(_, original)| *original);
+        self.original_offset(range.start)..end
+    }
+}
+
+/// A character after applying the Japanese specific rules, along with the byte offset of the
+/// original characters it was created from
+struct Unit {
+    c: char,
+    start: usize,
+}
+
+/// Normalizes `text` by the rules enabled in `options`
+pub fn normalize(text: &str, options: &NormalizeOptions) -> Normalized {
+    let mut units: Vec<Unit> = Vec::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let mut c = normalize_char(c, units.last().map(|unit| unit.c), options);
+
+        if let Some(&(_, mark)) = chars.peek() {
+            let mergeable = match mark {
+                'ﾞ' | 'ﾟ' => options.width || options.combining_marks,
+                '\u{3099}' | '\u{309A}' | '゛' | '゜' => options.combining_marks,
+                _ => false,
+            };
+
+            if let Some(merged) = compose_kana(c, mark).filter(|_| mergeable) {
+                c = merged;
+                chars.next();
+            }
+        }
+
+        units.push(Unit { c, start });
+    }
+
+    let mut normalized = String::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(units.len() + 1);
+
+    if options.compatibility {
+        // NFKC only combines a character with the combining characters following it, so each
+        // such cluster can be normalized on its own
+        let mut cluster = String::new();
+        let mut cluster_start = 0;
+
+        for unit in &units {
+            if !cluster.is_empty() && starts_cluster(&cluster, unit.c) {
+                offsets.push((normalized.len(), cluster_start));
+                normalized.extend(cluster.nfkc());
+                cluster.clear();
+            }
+            if cluster.is_empty() {
+                cluster_start = unit.start;
+            }
+            cluster.push(unit.c);
+        }
+
+        if !cluster.is_empty() {
+            offsets.push((normalized.len(), cluster_start));
+            normalized.extend(cluster.nfkc());
+        }
+    } else {
+        for unit in &units {
+            offsets.push((normalized.len(), unit.start));
+            normalized.push(unit.c);
+        }
+    }
+
+    offsets.push((normalized.len(), text.len()));
+    Normalized {
+        text: normalized,
+        offsets,
+    }
+}
+
+/// Returns `true` if NFKC leaves `c` apart from `cluster`, the characters before it
+fn starts_cluster(cluster: &str, c: char) -> bool {
+    let first = match c.nfkc().next() {
+        Some(first) => first,
+        None => return false,
+    };
+    let last = cluster.nfkc().last();
+
+    canonical_combining_class(first) == 0 && last.and_then(|last| compose(last, first)).is_none()
+}
+
+/// Applies the rules replacing a single character. `prev` is the normalized character before
+fn normalize_char(c: char, prev: Option<char>, options: &NormalizeOptions) -> char {
+    let c = if options.width {
+        full_width_to_ascii(half_to_full_width_kana(c))
+    } else {
+        c
+    };
+
+    match c {
+        '\t' | '\u{A0}' | '\u{2000}'..='\u{200A}' | '\u{202F}' | '\u{205F}' | '\u{3000}'
+            if options.whitespace =>
+        {
+            ' '
+        }
+        '-' | '－' | '‐' | '‑' | '–' | '—' | '―' | '─' | '━'
+            if options.prolonged_sound_marks && prev.is_some_and(is_kana) =>
+        {
+            'ー'
+        }
+        _ => c,
+    }
+}
+
+/// Merges a kana with a following voiced or semi voiced sound mark
+fn compose_kana(c: char, mark: char) -> Option<char> {
+    let katakana = hiragana_to_katakana_char(c);
+    let composed = match mark {
+        'ﾞ' | '\u{3099}' | '゛' => voiced(katakana)?,
+        'ﾟ' | '\u{309A}' | '゜' => semi_voiced(katakana)?,
+        _ => return None,
+    };
+
+    if is_hiragana(c) {
+        // ヷ has no hiragana
+        Some(katakana_to_hiragana_char(composed)).filter(|composed| is_hiragana(*composed))
+    } else {
+        Some(composed)
+    }
+}
+
+impl Parser {
+    /// Normalizes the input by `options` before parsing it. The morphemes still report their
+    /// surface and offsets within the original input, while their features are the ones of the
+    /// normalized text
+    pub fn with_normalization(mut self, options: NormalizeOptions) -> Self {
+        self.normalization = Some(options);
+        self
+    }
+
+    /// Returns the normalization applied to the input, if any
+    pub fn normalization(&self) -> Option<&NormalizeOptions> {
+        self.normalization.as_ref()
+    }
+
+    /// Segments `text` into morphemes, normalizing it first if enabled. Their `start` is the byte
+    /// offset within `text`
+    pub(crate) fn tag<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+    ) -> Vec<RawMorpheme<'dict, 'text>> {
+        let options = match &self.normalization {
+            Some(options) => options,
+            None => return self.tag_dictionaries(text),
+        };
+
+        let normalized = normalize(text, options);
+        normalized.restore(text, self.tag_dictionaries(&normalized.text))
+    }
+
+    /// Same as [`Parser::tag`] for the `n` cheapest paths through the lattice, along with their
+    /// costs
+    pub(crate) fn tag_nbest<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+        n: usize,
+    ) -> Vec<(Vec<RawMorpheme<'dict, 'text>>, i32)> {
+        let options = match &self.normalization {
+            Some(options) => options,
+            None => return self.tag_paths(text, n),
+        };
+
+        let normalized = normalize(text, options);
+        self.tag_paths(&normalized.text, n)
+            .into_iter()
+            .map(|(morphemes, cost)| (normalized.restore(text, morphemes), cost))
+            .collect()
+    }
+}
+
+impl Normalized {
+    /// Maps morphemes of the normalized text back onto the original `text`
+    fn restore<'text, 'dict>(
+        &self,
+        text: &'text str,
+        morphemes: Vec<RawMorpheme<'dict, '_>>,
+    ) -> Vec<RawMorpheme<'dict, 'text>> {
+        morphemes
+            .into_iter()
+            .map(|raw| {
+                let range = self.original_range(raw.start..raw.start + raw.surface.len());
+                RawMorpheme {
+                    surface: &text[range.clone()],
+                    feature: raw.feature,
+                    start: range.start,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalized(text: &str) -> String {
+        normalize(text, &NormalizeOptions::default()).text
+    }
+
+    #[test]
+    fn compatibility_characters() {
+        assert_eq!(normalized("①"), "1");
+        assert_eq!(normalized("㍻"), "平成");
+        assert_eq!(normalized("㌔"), "キロ");
+        assert_eq!(normalized("ﬁ"), "fi");
+        assert_eq!(normalized("ＡＢＣ１２３"), "ABC123");
+
+        let options = NormalizeOptions {
+            compatibility: false,
+            ..NormalizeOptions::default()
+        };
+        assert_eq!(normalize("①㍻", &options).text, "①㍻");
+    }
+
+    #[test]
+    fn voiced_sound_marks() {
+        assert_eq!(normalized("ｶﾞｷﾞﾊﾟ"), "ガギパ");
+        assert_eq!(normalized("か\u{3099}"), "が");
+        assert_eq!(normalized("か゛は゜"), "がぱ");
+        assert_eq!(normalized("ウ゛"), "ヴ");
+
+        let options = NormalizeOptions {
+            combining_marks: false,
+            ..NormalizeOptions::default()
+        };
+        // NFKC still composes the combining mark
+        assert_eq!(normalize("か\u{3099}", &options).text, "が");
+    }
+
+    #[test]
+    fn whitespace_and_dashes() {
+        assert_eq!(normalized("a\u{A0}b\u{3000}c\td"), "a b c d");
+        assert_eq!(normalized("ラ－メン"), "ラーメン");
+        assert_eq!(normalized("ら-めん"), "らーめん");
+        assert_eq!(normalized("1-2"), "1-2");
+    }
+
+    #[test]
+    fn offsets() {
+        let text = "ｶﾞ㍻か゛1";
+        let normalized = normalize(text, &NormalizeOptions::default());
+        assert_eq!(normalized.text, "ガ平成が1");
+
+        // ガ was created from ｶﾞ
+        assert_eq!(normalized.original_range(0..3), 0..6);
+        // 平成 was created from ㍻
+        assert_eq!(normalized.original_range(3..9), 6..9);
+        assert_eq!(normalized.original_range(3..6), 6..9);
+        assert_eq!(normalized.original_range(6..9), 6..9);
+        // が was created from か゛
+        assert_eq!(normalized.original_range(9..12), 9..15);
+        assert_eq!(normalized.original_range(12..13), 15..16);
+        assert_eq!(normalized.original_offset(13), text.len());
+        assert_eq!(&text[normalized.original_range(0..13)], text);
+    }
+
+    #[test]
+    fn empty() {
+        let normalized = normalize("", &NormalizeOptions::default());
+        assert_eq!(normalized.text, "");
+        assert_eq!(normalized.original_range(0..0), 0..0);
+    }
+}
@@ -0,0 +1,568 @@
+use std::ops::Range;
+
+use crate::{
+    AdjectiveType, Conjungation, ConjungationForm, ConjungationKind, Morpheme, NounType, Origin,
+    ParticleType, SyllableRow, VerbType, WordClass,
+};
+
+/// Owned version of [`Morpheme`], which borrows neither from the [`Parser`](crate::Parser)
+/// nor from the parsed text
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MorphemeBuf {
+    pub surface: String,
+    pub basic: String,
+    pub word_class: WordClassBuf,
+    pub conjungation: ConjungationBuf,
+    pub origin: Option<Origin>,
+    pub reading: String,
+    pub lexeme: String,
+    /// Byte offset of the morpheme within the parsed text
+    pub start: usize,
+    /// The raw, comma separated UniDic feature string
+    pub feature: String,
+}
+
+impl MorphemeBuf {
+    /// Returns a [`Morpheme`] borrowing from `self`
+    pub fn as_morpheme(&self) -> Morpheme<'_, '_> {
+        Morpheme {
+            surface: &self.surface,
+            basic: &self.basic,
+            word_class: self.word_class.as_borrowed(),
+            conjungation: self.conjungation.as_borrowed(),
+            origin: self.origin,
+            reading: &self.reading,
+            lexeme: &self.lexeme,
+            start: self.start,
+            feature: &self.feature,
+        }
+    }
+
+    /// Returns the byte range of the morpheme within the parsed text
+    pub fn byte_range(&self) -> Range<usize> {
+        self.start..self.start + self.surface.len()
+    }
+}
+
+impl<'dict, 'input> From<Morpheme<'dict, 'input>> for MorphemeBuf {
+    fn from(morph: Morpheme<'dict, 'input>) -> Self {
+        MorphemeBuf {
+            surface: morph.surface.to_string(),
+            basic: morph.basic.to_string(),
+            word_class: morph.word_class.into(),
+            conjungation: morph.conjungation.into(),
+            origin: morph.origin,
+            reading: morph.reading.to_string(),
+            lexeme: morph.lexeme.to_string(),
+            start: morph.start,
+            feature: morph.feature.to_string(),
+        }
+    }
+}
+
+/// Owned version of [`Conjungation`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConjungationBuf {
+    pub kind: ConjungationKindBuf,
+    pub form: ConjungationFormBuf,
+}
+
+impl ConjungationBuf {
+    /// Returns a [`Conjungation`] borrowing from `self`
+    pub fn as_borrowed(&self) -> Conjungation<'_> {
+        Conjungation {
+            kind: self.kind.as_borrowed(),
+            form: self.form.as_borrowed(),
+        }
+    }
+}
+
+impl<'a> From<Conjungation<'a>> for ConjungationBuf {
+    fn from(conjungation: Conjungation<'a>) -> Self {
+        ConjungationBuf {
+            kind: conjungation.kind.into(),
+            form: conjungation.form.into(),
+        }
+    }
+}
+
+/// Owned version of [`WordClass`]
+#[derive(Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WordClassBuf {
+    Particle(ParticleTypeBuf),
+    Verb(VerbTypeBuf),
+    Adjective(AdjectiveTypeBuf),
+    #[default]
+    Adverb,
+    Noun(NounTypeBuf),
+    Pronoun,
+    Interjection,
+    Symbol,
+    Conjungtion,
+    Suffix,
+    Prefix,
+    PreNoun,
+    Space,
+    Unknown(String),
+}
+
+impl WordClassBuf {
+    /// Returns a [`WordClass`] borrowing from `self`
+    pub fn as_borrowed(&self) -> WordClass<'_> {
+        match self {
+            Self::Particle(t) => WordClass::Particle(t.as_borrowed()),
+            Self::Verb(t) => WordClass::Verb(t.as_borrowed()),
+            Self::Adjective(t) => WordClass::Adjective(t.as_borrowed()),
+            Self::Adverb => WordClass::Adverb,
+            Self::Noun(t) => WordClass::Noun(t.as_borrowed()),
+            Self::Pronoun => WordClass::Pronoun,
+            Self::Interjection => WordClass::Interjection,
+            Self::Symbol => WordClass::Symbol,
+            Self::Conjungtion => WordClass::Conjungtion,
+            Self::Suffix => WordClass::Suffix,
+            Self::Prefix => WordClass::Prefix,
+            Self::PreNoun => WordClass::PreNoun,
+            Self::Space => WordClass::Space,
+            Self::Unknown(s) => WordClass::Unknown(s),
+        }
+    }
+}
+
+impl<'a> From<WordClass<'a>> for WordClassBuf {
+    fn from(value: WordClass<'a>) -> Self {
+        match value {
+            WordClass::Particle(t) => Self::Particle(t.into()),
+            WordClass::Verb(t) => Self::Verb(t.into()),
+            WordClass::Adjective(t) => Self::Adjective(t.into()),
+            WordClass::Adverb => Self::Adverb,
+            WordClass::Noun(t) => Self::Noun(t.into()),
+            WordClass::Pronoun => Self::Pronoun,
+            WordClass::Interjection => Self::Interjection,
+            WordClass::Symbol => Self::Symbol,
+            WordClass::Conjungtion => Self::Conjungtion,
+            WordClass::Suffix => Self::Suffix,
+            WordClass::Prefix => Self::Prefix,
+            WordClass::PreNoun => Self::PreNoun,
+            WordClass::Space => Self::Space,
+            WordClass::Unknown(s) => Self::Unknown(s.to_string()),
+        }
+    }
+}
+
+/// Owned version of [`NounType`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NounTypeBuf {
+    Common,
+    Proper,
+    Numeral,
+    Suffix,
+    Jodoushi,
+    Unknown(String),
+}
+
+impl NounTypeBuf {
+    /// Returns a [`NounType`] borrowing from `self`
+    pub fn as_borrowed(&self) -> NounType<'_> {
+        match self {
+            Self::Common => NounType::Common,
+            Self::Proper => NounType::Proper,
+            Self::Numeral => NounType::Numeral,
+            Self::Suffix => NounType::Suffix,
+            Self::Jodoushi => NounType::Jodoushi,
+            Self::Unknown(s) => NounType::Unknown(s),
+        }
+    }
+}
+
+impl<'a> From<NounType<'a>> for NounTypeBuf {
+    fn from(value: NounType<'a>) -> Self {
+        match value {
+            NounType::Common => Self::Common,
+            NounType::Proper => Self::Proper,
+            NounType::Numeral => Self::Numeral,
+            NounType::Suffix => Self::Suffix,
+            NounType::Jodoushi => Self::Jodoushi,
+            NounType::Unknown(s) => Self::Unknown(s.to_string()),
+        }
+    }
+}
+
+/// Owned version of [`ParticleType`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParticleTypeBuf {
+    Connecting,
+    SentenceEnding,
+    CaseMaking,
+    Conjungtion,
+    Adverbial,
+    Nominalizing,
+    Unknown(String),
+}
+
+impl ParticleTypeBuf {
+    /// Returns a [`ParticleType`] borrowing from `self`
+    pub fn as_borrowed(&self) -> ParticleType<'_> {
+        match self {
+            Self::Connecting => ParticleType::Connecting,
+            Self::SentenceEnding => ParticleType::SentenceEnding,
+            Self::CaseMaking => ParticleType::CaseMaking,
+            Self::Conjungtion => ParticleType::Conjungtion,
+            Self::Adverbial => ParticleType::Adverbial,
+            Self::Nominalizing => ParticleType::Nominalizing,
+            Self::Unknown(s) => ParticleType::Unknown(s),
+        }
+    }
+}
+
+impl<'a> From<ParticleType<'a>> for ParticleTypeBuf {
+    fn from(value: ParticleType<'a>) -> Self {
+        match value {
+            ParticleType::Connecting => Self::Connecting,
+            ParticleType::SentenceEnding => Self::SentenceEnding,
+            ParticleType::CaseMaking => Self::CaseMaking,
+            ParticleType::Conjungtion => Self::Conjungtion,
+            ParticleType::Adverbial => Self::Adverbial,
+            ParticleType::Nominalizing => Self::Nominalizing,
+            ParticleType::Unknown(s) => Self::Unknown(s.to_string()),
+        }
+    }
+}
+
+/// Owned version of [`AdjectiveType`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AdjectiveTypeBuf {
+    I,
+    Na,
+    Unknown(String),
+}
+
+impl AdjectiveTypeBuf {
+    /// Returns a [`AdjectiveType`] borrowing from `self`
+    pub fn as_borrowed(&self) -> AdjectiveType<'_> {
+        match self {
+            Self::I => AdjectiveType::I,
+            Self::Na => AdjectiveType::Na,
+            Self::Unknown(s) => AdjectiveType::Unknown(s),
+        }
+    }
+}
+
+impl<'a> From<AdjectiveType<'a>> for AdjectiveTypeBuf {
+    fn from(value: AdjectiveType<'a>) -> Self {
+        match value {
+            AdjectiveType::I => Self::I,
+            AdjectiveType::Na => Self::Na,
+            AdjectiveType::Unknown(s) => Self::Unknown(s.to_string()),
+        }
+    }
+}
+
+/// Owned version of [`VerbType`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerbTypeBuf {
+    Auxilary(String),
+    Godan(SyllableRowBuf),
+    Ichidan(SyllableRowBuf),
+    IchidanEruConjungation,
+    IrrWrittenLang,
+    Suru,
+    Kuru,
+    Zuru,
+    IrregRu,
+    IrregNu,
+    Unknown(String),
+}
+
+impl VerbTypeBuf {
+    /// Returns a [`VerbType`] borrowing from `self`
+    pub fn as_borrowed(&self) -> VerbType<'_> {
+        match self {
+            Self::Auxilary(s) => VerbType::Auxilary(s),
+            Self::Godan(t) => VerbType::Godan(t.as_borrowed()),
+            Self::Ichidan(t) => VerbType::Ichidan(t.as_borrowed()),
+            Self::IchidanEruConjungation => VerbType::IchidanEruConjungation,
+            Self::IrrWrittenLang => VerbType::IrrWrittenLang,
+            Self::Suru => VerbType::Suru,
+            Self::Kuru => VerbType::Kuru,
+            Self::Zuru => VerbType::Zuru,
+            Self::IrregRu => VerbType::IrregRu,
+            Self::IrregNu => VerbType::IrregNu,
+            Self::Unknown(s) => VerbType::Unknown(s),
+        }
+    }
+}
+
+impl<'a> From<VerbType<'a>> for VerbTypeBuf {
+    fn from(value: VerbType<'a>) -> Self {
+        match value {
+            VerbType::Auxilary(s) => Self::Auxilary(s.to_string()),
+            VerbType::Godan(t) => Self::Godan(t.into()),
+            VerbType::Ichidan(t) => Self::Ichidan(t.into()),
+            VerbType::IchidanEruConjungation => Self::IchidanEruConjungation,
+            VerbType::IrrWrittenLang => Self::IrrWrittenLang,
+            VerbType::Suru => Self::Suru,
+            VerbType::Kuru => Self::Kuru,
+            VerbType::Zuru => Self::Zuru,
+            VerbType::IrregRu => Self::IrregRu,
+            VerbType::IrregNu => Self::IrregNu,
+            VerbType::Unknown(s) => Self::Unknown(s.to_string()),
+        }
+    }
+}
+
+/// Owned version of [`ConjungationKind`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConjungationKindBuf {
+    None,
+    Godan(SyllableRowBuf),
+    KamiIchidan(SyllableRowBuf),
+    ShimoIchidan(SyllableRowBuf),
+    Ichidan(SyllableRowBuf),
+    Kuru,
+    Suru,
+    Zuru,
+    IrregRu,
+    IrregNu,
+    Adjective,
+    Auxilary(String),
+    Invariant,
+    ClassicalYodan(SyllableRowBuf),
+    ClassicalKamiIchidan(SyllableRowBuf),
+    ClassicalKamiNidan(SyllableRowBuf),
+    ClassicalShimoIchidan(SyllableRowBuf),
+    ClassicalShimoNidan(SyllableRowBuf),
+    ClassicalKuru,
+    ClassicalSuru,
+    ClassicalIrregRu,
+    ClassicalIrregNu,
+    ClassicalAdjectiveKu,
+    ClassicalAdjectiveShiku,
+    ClassicalAuxilary(String),
+    Unknown(String),
+}
+
+impl ConjungationKindBuf {
+    /// Returns a [`ConjungationKind`] borrowing from `self`
+    pub fn as_borrowed(&self) -> ConjungationKind<'_> {
+        match self {
+            Self::None => ConjungationKind::None,
+            Self::Godan(t) => ConjungationKind::Godan(t.as_borrowed()),
+            Self::KamiIchidan(t) => ConjungationKind::KamiIchidan(t.as_borrowed()),
+            Self::ShimoIchidan(t) => ConjungationKind::ShimoIchidan(t.as_borrowed()),
+            Self::Ichidan(t) => ConjungationKind::Ichidan(t.as_borrowed()),
+            Self::Kuru => ConjungationKind::Kuru,
+            Self::Suru => ConjungationKind::Suru,
+            Self::Zuru => ConjungationKind::Zuru,
+            Self::IrregRu => ConjungationKind::IrregRu,
+            Self::IrregNu => ConjungationKind::IrregNu,
+            Self::Adjective => ConjungationKind::Adjective,
+            Self::Auxilary(s) => ConjungationKind::Auxilary(s),
+            Self::Invariant => ConjungationKind::Invariant,
+            Self::ClassicalYodan(t) => ConjungationKind::ClassicalYodan(t.as_borrowed()),
+            Self::ClassicalKamiIchidan(t) => {
+                ConjungationKind::ClassicalKamiIchidan(t.as_borrowed())
+            }
+            Self::ClassicalKamiNidan(t) => ConjungationKind::ClassicalKamiNidan(t.as_borrowed()),
+            Self::ClassicalShimoIchidan(t) => {
+                ConjungationKind::ClassicalShimoIchidan(t.as_borrowed())
+            }
+            Self::ClassicalShimoNidan(t) => ConjungationKind::ClassicalShimoNidan(t.as_borrowed()),
+            Self::ClassicalKuru => ConjungationKind::ClassicalKuru,
+            Self::ClassicalSuru => ConjungationKind::ClassicalSuru,
+            Self::ClassicalIrregRu => ConjungationKind::ClassicalIrregRu,
+            Self::ClassicalIrregNu => ConjungationKind::ClassicalIrregNu,
+            Self::ClassicalAdjectiveKu => ConjungationKind::ClassicalAdjectiveKu,
+            Self::ClassicalAdjectiveShiku => ConjungationKind::ClassicalAdjectiveShiku,
+            Self::ClassicalAuxilary(s) => ConjungationKind::ClassicalAuxilary(s),
+            Self::Unknown(s) => ConjungationKind::Unknown(s),
+        }
+    }
+}
+
+impl<'a> From<ConjungationKind<'a>> for ConjungationKindBuf {
+    fn from(value: ConjungationKind<'a>) -> Self {
+        match value {
+            ConjungationKind::None => Self::None,
+            ConjungationKind::Godan(t) => Self::Godan(t.into()),
+            ConjungationKind::KamiIchidan(t) => Self::KamiIchidan(t.into()),
+            ConjungationKind::ShimoIchidan(t) => Self::ShimoIchidan(t.into()),
+            ConjungationKind::Ichidan(t) => Self::Ichidan(t.into()),
+            ConjungationKind::Kuru => Self::Kuru,
+            ConjungationKind::Suru => Self::Suru,
+            ConjungationKind::Zuru => Self::Zuru,
+            ConjungationKind::IrregRu => Self::IrregRu,
+            ConjungationKind::IrregNu => Self::IrregNu,
+            ConjungationKind::Adjective => Self::Adjective,
+            ConjungationKind::Auxilary(s) => Self::Auxilary(s.to_string()),
+            ConjungationKind::Invariant => Self::Invariant,
+            ConjungationKind::ClassicalYodan(t) => Self::ClassicalYodan(t.into()),
+            ConjungationKind::ClassicalKamiIchidan(t) => Self::ClassicalKamiIchidan(t.into()),
+            ConjungationKind::ClassicalKamiNidan(t) => Self::ClassicalKamiNidan(t.into()),
+            ConjungationKind::ClassicalShimoIchidan(t) => Self::ClassicalShimoIchidan(t.into()),
+            ConjungationKind::ClassicalShimoNidan(t) => Self::ClassicalShimoNidan(t.into()),
+            ConjungationKind::ClassicalKuru => Self::ClassicalKuru,
+            ConjungationKind::ClassicalSuru => Self::ClassicalSuru,
+            ConjungationKind::ClassicalIrregRu => Self::ClassicalIrregRu,
+            ConjungationKind::ClassicalIrregNu => Self::ClassicalIrregNu,
+            ConjungationKind::ClassicalAdjectiveKu => Self::ClassicalAdjectiveKu,
+            ConjungationKind::ClassicalAdjectiveShiku => Self::ClassicalAdjectiveShiku,
+            ConjungationKind::ClassicalAuxilary(s) => Self::ClassicalAuxilary(s.to_string()),
+            ConjungationKind::Unknown(s) => Self::Unknown(s.to_string()),
+        }
+    }
+}
+
+/// Owned version of [`ConjungationForm`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConjungationFormBuf {
+    None,
+    Plain,
+    Imperative,
+    Negative,
+    Attributive,
+    Continuous,
+    Conditional,
+    Stem,
+    Realis,
+    Kugohou,
+    Unknown(String),
+}
+
+impl ConjungationFormBuf {
+    /// Returns a [`ConjungationForm`] borrowing from `self`
+    pub fn as_borrowed(&self) -> ConjungationForm<'_> {
+        match self {
+            Self::None => ConjungationForm::None,
+            Self::Plain => ConjungationForm::Plain,
+            Self::Imperative => ConjungationForm::Imperative,
+            Self::Negative => ConjungationForm::Negative,
+            Self::Attributive => ConjungationForm::Attributive,
+            Self::Continuous => ConjungationForm::Continuous,
+            Self::Conditional => ConjungationForm::Conditional,
+            Self::Stem => ConjungationForm::Stem,
+            Self::Realis => ConjungationForm::Realis,
+            Self::Kugohou => ConjungationForm::Kugohou,
+            Self::Unknown(s) => ConjungationForm::Unknown(s),
+        }
+    }
+}
+
+impl<'a> From<ConjungationForm<'a>> for ConjungationFormBuf {
+    fn from(value: ConjungationForm<'a>) -> Self {
+        match value {
+            ConjungationForm::None => Self::None,
+            ConjungationForm::Plain => Self::Plain,
+            ConjungationForm::Imperative => Self::Imperative,
+            ConjungationForm::Negative => Self::Negative,
+            ConjungationForm::Attributive => Self::Attributive,
+            ConjungationForm::Continuous => Self::Continuous,
+            ConjungationForm::Conditional => Self::Conditional,
+            ConjungationForm::Stem => Self::Stem,
+            ConjungationForm::Realis => Self::Realis,
+            ConjungationForm::Kugohou => Self::Kugohou,
+            ConjungationForm::Unknown(s) => Self::Unknown(s.to_string()),
+        }
+    }
+}
+
+/// Owned version of [`SyllableRow`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SyllableRowBuf {
+    G,
+    K,
+    M,
+    A,
+    R,
+    S,
+    Z,
+    T,
+    D,
+    B,
+    H,
+    P,
+    N,
+    Wa,
+    Y,
+    Unknown(String),
+}
+
+impl SyllableRowBuf {
+    /// Returns a [`SyllableRow`] borrowing from `self`
+    pub fn as_borrowed(&self) -> SyllableRow<'_> {
+        match self {
+            Self::G => SyllableRow::G,
+            Self::K => SyllableRow::K,
+            Self::M => SyllableRow::M,
+            Self::A => SyllableRow::A,
+            Self::R => SyllableRow::R,
+            Self::S => SyllableRow::S,
+            Self::Z => SyllableRow::Z,
+            Self::T => SyllableRow::T,
+            Self::D => SyllableRow::D,
+            Self::B => SyllableRow::B,
+            Self::H => SyllableRow::H,
+            Self::P => SyllableRow::P,
+            Self::N => SyllableRow::N,
+            Self::Wa => SyllableRow::Wa,
+            Self::Y => SyllableRow::Y,
+            Self::Unknown(s) => SyllableRow::Unknown(s),
+        }
+    }
+}
+
+impl<'a> From<SyllableRow<'a>> for SyllableRowBuf {
+    fn from(value: SyllableRow<'a>) -> Self {
+        match value {
+            SyllableRow::G => Self::G,
+            SyllableRow::K => Self::K,
+            SyllableRow::M => Self::M,
+            SyllableRow::A => Self::A,
+            SyllableRow::R => Self::R,
+            SyllableRow::S => Self::S,
+            SyllableRow::Z => Self::Z,
+            SyllableRow::T => Self::T,
+            SyllableRow::D => Self::D,
+            SyllableRow::B => Self::B,
+            SyllableRow::H => Self::H,
+            SyllableRow::P => Self::P,
+            SyllableRow::N => Self::N,
+            SyllableRow::Wa => Self::Wa,
+            SyllableRow::Y => Self::Y,
+            SyllableRow::Unknown(s) => Self::Unknown(s.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::morph;
+
+    #[test]
+    fn to_buf_works_on_references() {
+        let feature =
+            "名詞,固有名詞,地名,一般,*,*,トウキョウ,東京,東京,トーキョー,東京,トーキョー,固";
+        let morpheme = morph("東京", 3, feature);
+        let morphemes = [morpheme];
+
+        let owned: Vec<MorphemeBuf> = morphemes.iter().map(|m| m.to_buf()).collect();
+        assert_eq!(owned[0].surface, "東京");
+        assert_eq!(owned[0].byte_range(), 3..9);
+        assert_eq!(owned[0].as_morpheme(), morpheme);
+        assert_eq!(MorphemeBuf::from(morpheme), owned[0]);
+        assert_eq!(
+            morpheme.word_class.to_buf().as_borrowed(),
+            morpheme.word_class
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use std::ops::Range;
+
+use crate::Morpheme;
+
+/// A position within a text, as 0 based line and column
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineCol {
+    pub line: usize,
+    /// Column in characters
+    pub column: usize,
+    /// Column in UTF-16 code units, as used by JavaScript and the language server protocol
+    pub utf16_column: usize,
+}
+
+/// Number of characters after which a checkpoint is stored, so lookups within long lines only
+/// scan up to this many characters
+const CHECKPOINT_CHARS: usize = 64;
+
+/// Byte, character and UTF-16 offsets of the same position
+#[derive(Clone, Copy, Debug)]
+struct Offsets {
+    byte: usize,
+    char: usize,
+    utf16: usize,
+}
+
+/// Maps byte offsets within a text to character and UTF-16 offsets and to lines and columns.
+/// Building it scans the text once, lookups scan at most 64 characters from the closest
+/// checkpoint before the offset. Lines end after `\n`
+#[derive(Clone, Debug)]
+pub struct LineIndex<'text> {
+    text: &'text str,
+    lines: Vec<Offsets>,
+    /// The start of every line and every 64th character within a line
+    checkpoints: Vec<Offsets>,
+}
+
+impl<'text> LineIndex<'text> {
+    /// Indexes the lines of `text`
+    pub fn new(text: &'text str) -> Self {
+        let start = Offsets {
+            byte: 0,
+            char: 0,
+            utf16: 0,
+        };
+        let mut lines = vec![start];
+        let mut checkpoints = vec![start];
+        let mut utf16 = 0;
+
+        for (chars, (i, c)) in text.char_indices().enumerate() {
+            utf16 += c.len_utf16();
+            let offsets = Offsets {
+                byte: i + c.len_utf8(),
+                char: chars + 1,
+                utf16,
+            };
+            if c == '\n' {
+                lines.push(offsets);
+                checkpoints.push(offsets);
+            } else if offsets.char - checkpoints[checkpoints.len() - 1].char == CHECKPOINT_CHARS {
+                checkpoints.push(offsets);
+            }
+        }
+
+        LineIndex {
+            text,
+            lines,
+            checkpoints,
+        }
+    }
+
+    /// Returns the indexed text
+    pub fn text(&self) -> &'text str {
+        self.text
+    }
+
+    /// Returns the amount of lines of the text
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns the character offset of the byte offset `byte`
+    ///
+    /// # Panics
+    /// Panics if `byte` is not on a character boundary of the text
+    pub fn char_offset(&self, byte: usize) -> usize {
+        self.offsets(byte).char
+    }
+
+    /// Returns the UTF-16 offset of the byte offset `byte`
+    ///
+    /// # Panics
+    /// Panics if `byte` is not on a character boundary of the text
+    pub fn utf16_offset(&self, byte: usize) -> usize {
+        self.offsets(byte).utf16
+    }
+
+    /// Returns the line and column of the byte offset `byte`
+    ///
+    /// # Panics
+    /// Panics if `byte` is not on a character boundary of the text
+    pub fn line_col(&self, byte: usize) -> LineCol {
+        let line = self.line(byte);
+        let (start, offsets) = (self.lines[line], self.offsets(byte));
+        LineCol {
+            line,
+            column: offsets.char - start.char,
+            utf16_column: offsets.utf16 - start.utf16,
+        }
+    }
+
+    /// Returns the byte offset of the `line` and `column` of `position`, or `None` if the line lies
+    /// outside of the text. Columns beyond the end of the line are clamped to it
+    pub fn byte_offset(&self, position: LineCol) -> Option<usize> {
+        let start = self.lines.get(position.line)?;
+        let end = self
+            .lines
+            .get(position.line + 1)
+            .map_or(self.text.len(), |next| next.byte);
+
+        let char = start.char + position.column;
+        let checkpoint = self.checkpoints[self.checkpoints.partition_point(|c| c.char <= char) - 1];
+        if checkpoint.byte >= end {
+            return Some(end);
+        }
+
+        let offset = self.text[checkpoint.byte..end]
+            .char_indices()
+            .nth(char - checkpoint.char)
+            .map_or(end, |(i, _)| checkpoint.byte + i);
+        Some(offset)
+    }
+
+    /// Returns the 0 based line containing `byte`
+    fn line(&self, byte: usize) -> usize {
+        self.lines.partition_point(|line| line.byte <= byte) - 1
+    }
+
+    /// Returns the offsets of `byte`, counted from the closest checkpoint before it
+    fn offsets(&self, byte: usize) -> Offsets {
+        let checkpoint = self.checkpoints[self.checkpoints.partition_point(|c| c.byte <= byte) - 1];
+        let before = &self.text[checkpoint.byte..byte];
+        Offsets {
+            byte,
+            char: checkpoint.char + before.chars().count(),
+            utf16: checkpoint.utf16 + before.encode_utf16().count(),
+        }
+    }
+}
+
+impl<'dict, 'input> Morpheme<'dict, 'input> {
+    /// Returns the byte offset right after the morpheme
+    pub fn end(&self) -> usize {
+        self.start + self.surface.len()
+    }
+
+    /// Returns the byte range of the morpheme within the parsed text. Slicing the text with it
+    /// returns the surface
+    pub fn byte_range(&self) -> Range<usize> {
+        self.start..self.end()
+    }
+
+    /// Returns the character range of the morpheme within the text of `index`
+    pub fn char_range(&self, index: &LineIndex) -> Range<usize> {
+        let start = index.char_offset(self.start);
+        start..start + self.surface.chars().count()
+    }
+
+    /// Returns the UTF-16 range of the morpheme within the text of `index`
+    pub fn utf16_range(&self, index: &LineIndex) -> Range<usize> {
+        let start = index.utf16_offset(self.start);
+        start..start + self.surface.encode_utf16().count()
+    }
+
+    /// Returns the line and column the morpheme starts at within the text of `index`
+    pub fn line_col(&self, index: &LineIndex) -> LineCol {
+        index.line_col(self.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mixes ASCII, kana, kanji and characters outside of the BMP over several lines, with a
+    /// line longer than the distance between checkpoints
+    fn text() -> String {
+        let long: String = "aあ𠮷".repeat(50);
+        format!("ab\nかな漢字\r\n{}\n\n𠮷x", long)
+    }
+
+    #[test]
+    fn offsets_match_a_scan() {
+        let text = text();
+        let index = LineIndex::new(&text);
+        assert_eq!(index.line_count(), 5);
+
+        let (mut chars, mut utf16, mut line, mut column, mut utf16_column) = (0, 0, 0, 0, 0);
+        for (byte, c) in text.char_indices().chain(Some((text.len(), '\0'))) {
+            assert_eq!(index.char_offset(byte), chars);
+            assert_eq!(index.utf16_offset(byte), utf16);
+            let position = LineCol {
+                line,
+                column,
+                utf16_column,
+            };
+            assert_eq!(index.line_col(byte), position);
+            assert_eq!(index.byte_offset(position), Some(byte));
+
+            chars += 1;
+            utf16 += c.len_utf16();
+            if c == '\n' {
+                line += 1;
+                column = 0;
+                utf16_column = 0;
+            } else {
+                column += 1;
+                utf16_column += c.len_utf16();
+            }
+        }
+    }
+
+    #[test]
+    fn line_ends_and_clamping() {
+        let text = text();
+        let index = LineIndex::new(&text);
+        let at = |line, column| {
+            index.byte_offset(LineCol {
+                line,
+                column,
+                utf16_column: 0,
+            })
+        };
+
+        // CRLF lines end after the \n, so the \r is the last column of its line
+        let cr = text.find('\r').unwrap();
+        assert_eq!(index.line_col(cr).column, 4);
+        assert_eq!(at(1, 4), Some(cr));
+        assert_eq!(at(1, 5), Some(cr + 1));
+        assert_eq!(at(1, 100), Some(cr + 2));
+        assert_eq!(index.line_col(cr + 2).line, 2);
+
+        // The long line ends far after its last checkpoint, the empty line right away
+        let empty = text.find("\n\n").unwrap() + 1;
+        assert_eq!(at(2, 1000), Some(empty));
+        assert_eq!(at(3, 0), Some(empty));
+        assert_eq!(at(3, 5), Some(empty + 1));
+
+        // The last line has no \n
+        assert_eq!(at(4, 1), Some(text.len() - 1));
+        assert_eq!(at(4, 9), Some(text.len()));
+        assert_eq!(at(5, 0), None);
+        assert_eq!(index.line_col(text.len()).line, 4);
+    }
+
+    #[test]
+    fn morpheme_ranges() {
+        let text = "𠮷野家\nで食べる";
+        let index = LineIndex::new(text);
+        let morph = crate::morph("食べる", text.find('食').unwrap(), "動詞,一般");
+        assert_eq!(morph.char_range(&index), 5..8);
+        assert_eq!(morph.utf16_range(&index), 6..9);
+        assert_eq!(
+            morph.line_col(&index),
+            LineCol {
+                line: 1,
+                column: 1,
+                utf16_column: 1
+            }
+        );
+        assert_eq!(&text[morph.byte_range()], "食べる");
+    }
+}
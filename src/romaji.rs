@@ -0,0 +1,508 @@
+use crate::{group_words, kana::hiragana_to_katakana_char, morae, Morpheme, Parser, WordClass};
+
+/// A system to romanize Japanese with
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RomajiSystem {
+    /// ヘボン式, like shi, chi, tsu and fu
+    Hepburn,
+    /// 訓令式, like si, ti, tu and hu
+    Kunrei,
+    /// 日本式, like Kunrei-shiki but keeping ぢ, づ and を apart as di, du and wo
+    NihonShiki,
+}
+
+/// How long vowels are written
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LongVowels {
+    /// tōkyō
+    Macron,
+    /// tôkyô
+    Circumflex,
+    /// tookyoo
+    Double,
+}
+
+/// Options for romanizing text
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RomajiOptions {
+    pub system: RomajiSystem,
+    pub long_vowels: LongVowels,
+}
+
+impl RomajiOptions {
+    /// Uses the long vowels common for `system`, macrons for Hepburn and circumflexes otherwise
+    pub fn new(system: RomajiSystem) -> Self {
+        let long_vowels = match system {
+            RomajiSystem::Hepburn => LongVowels::Macron,
+            RomajiSystem::Kunrei | RomajiSystem::NihonShiki => LongVowels::Circumflex,
+        };
+        RomajiOptions {
+            system,
+            long_vowels,
+        }
+    }
+}
+
+impl Default for RomajiOptions {
+    fn default() -> Self {
+        Self::new(RomajiSystem::Hepburn)
+    }
+}
+
+/// Romanizations of single kana in Hepburn, Kunrei-shiki and Nihon-shiki
+const KANA: &[(char, [&str; 3])] = &[
+    ('ア', ["a", "a", "a"]),
+    ('イ', ["i", "i", "i"]),
+    ('ウ', ["u", "u", "u"]),
+    ('エ', ["e", "e", "e"]),
+    ('オ', ["o", "o", "o"]),
+    ('カ', ["ka", "ka", "ka"]),
+    ('キ', ["ki", "ki", "ki"]),
+    ('ク', ["ku", "ku", "ku"]),
+    ('ケ', ["ke", "ke", "ke"]),
+    ('コ', ["ko", "ko", "ko"]),
+    ('サ', ["sa", "sa", "sa"]),
+    ('シ', ["shi", "si", "si"]),
+    ('ス', ["su", "su", "su"]),
+    ('セ', ["se", "se", "se"]),
+    ('ソ', ["so", "so", "so"]),
+    ('タ', ["ta", "ta", "ta"]),
+    ('チ', ["chi", "ti", "ti"]),
+    ('ツ', ["tsu", "tu", "tu"]),
+    ('テ', ["te", "te", "te"]),
+    ('ト', ["to", "to", "to"]),
+    ('ナ', ["na", "na", "na"]),
+    ('ニ', ["ni", "ni", "ni"]),
+    ('ヌ', ["nu", "nu", "nu"]),
+    ('ネ', ["ne", "ne", "ne"]),
+    ('ノ', ["no", "no", "no"]),
+    ('ハ', ["ha", "ha", "ha"]),
+    ('ヒ', ["hi", "hi", "hi"]),
+    ('フ', ["fu", "hu", "hu"]),
+    ('ヘ', ["he", "he", "he"]),
+    ('ホ', ["ho", "ho", "ho"]),
+    ('マ', ["ma", "ma", "ma"]),
+    ('ミ', ["mi", "mi", "mi"]),
+    ('ム', ["mu", "mu", "mu"]),
+    ('メ', ["me", "me", "me"]),
+    ('モ', ["mo", "mo", "mo"]),
+    ('ヤ', ["ya", "ya", "ya"]),
+    ('ユ', ["yu", "yu", "yu"]),
+    ('ヨ', ["yo", "yo", "yo"]),
+    ('ラ', ["ra", "ra", "ra"]),
+    ('リ', ["ri", "ri", "ri"]),
+    ('ル', ["ru", "ru", "ru"]),
+    ('レ', ["re", "re", "re"]),
+    ('ロ', ["ro", "ro", "ro"]),
+    ('ワ', ["wa", "wa", "wa"]),
+    ('ヰ', ["i", "i", "wi"]),
+    ('ヱ', ["e", "e", "we"]),
+    ('ヲ', ["o", "o", "wo"]),
+    ('ン', ["n", "n", "n"]),
+    ('ガ', ["ga", "ga", "ga"]),
+    ('ギ', ["gi", "gi", "gi"]),
+    ('グ', ["gu", "gu", "gu"]),
+    ('ゲ', ["ge", "ge", "ge"]),
+    ('ゴ', ["go", "go", "go"]),
+    ('ザ', ["za", "za", "za"]),
+    ('ジ', ["ji", "zi", "zi"]),
+    ('ズ', ["zu", "zu", "zu"]),
+    ('ゼ', ["ze", "ze", "ze"]),
+    ('ゾ', ["zo", "zo", "zo"]),
+    ('ダ', ["da", "da", "da"]),
+    ('ヂ', ["ji", "zi", "di"]),
+    ('ヅ', ["zu", "zu", "du"]),
+    ('デ', ["de", "de", "de"]),
+    ('ド', ["do", "do", "do"]),
+    ('バ', ["ba", "ba", "ba"]),
+    ('ビ', ["bi", "bi", "bi"]),
+    ('ブ', ["bu", "bu", "bu"]),
+    ('ベ', ["be", "be", "be"]),
+    ('ボ', ["bo", "bo", "bo"]),
+    ('パ', ["pa", "pa", "pa"]),
+    ('ピ', ["pi", "pi", "pi"]),
+    ('プ', ["pu", "pu", "pu"]),
+    ('ペ', ["pe", "pe", "pe"]),
+    ('ポ', ["po", "po", "po"]),
+    ('ヴ', ["vu", "vu", "vu"]),
+    ('ァ', ["a", "a", "a"]),
+    ('ィ', ["i", "i", "i"]),
+    ('ゥ', ["u", "u", "u"]),
+    ('ェ', ["e", "e", "e"]),
+    ('ォ', ["o", "o", "o"]),
+    ('ャ', ["ya", "ya", "ya"]),
+    ('ュ', ["yu", "yu", "yu"]),
+    ('ョ', ["yo", "yo", "yo"]),
+    ('ヮ', ["wa", "wa", "wa"]),
+    ('ヵ', ["ka", "ka", "ka"]),
+    ('ヶ', ["ke", "ke", "ke"]),
+];
+
+impl RomajiSystem {
+    fn index(&self) -> usize {
+        match self {
+            Self::Hepburn => 0,
+            Self::Kunrei => 1,
+            Self::NihonShiki => 2,
+        }
+    }
+}
+
+/// Romanizes a single kana
+fn kana_romaji(c: char, system: RomajiSystem) -> Option<&'static str> {
+    let c = hiragana_to_katakana_char(c);
+    KANA.iter()
+        .find(|(kana, _)| *kana == c)
+        .map(|(_, romaji)| romaji[system.index()])
+}
+
+/// Romanizes a single mora, like キ or キャ
+fn mora_romaji(mora: &str, system: RomajiSystem) -> Option<String> {
+    let mut chars = mora.chars();
+    let base = kana_romaji(chars.next()?, system)?;
+    let small = match chars.next() {
+        Some(small) => hiragana_to_katakana_char(small),
+        None => return Some(base.to_string()),
+    };
+
+    let stem = base.trim_end_matches(|c| "aiueo".contains(c));
+    let romaji = match small {
+        // 拗音 like キャ or シュ
+        'ャ' | 'ュ' | 'ョ' => {
+            let vowel = &kana_romaji(small, system)?[1..];
+            if stem.ends_with('h') || stem == "j" {
+                format!("{}{}", stem, vowel)
+            } else {
+                format!("{}y{}", stem, vowel)
+            }
+        }
+        'ヮ' => format!("{}wa", stem),
+        // Loanword sounds like ファ or ティ, always spelled like Hepburn
+        _ => {
+            let vowel = kana_romaji(small, system)?;
+            let stem = kana_romaji(mora.chars().next()?, RomajiSystem::Hepburn)?
+                .trim_end_matches(|c| "aiueo".contains(c));
+            match stem {
+                "" if base == "i" => format!("y{}", vowel),
+                "" => format!("w{}", vowel),
+                stem => format!("{}{}", stem, vowel),
+            }
+        }
+    };
+    Some(romaji)
+}
+
+/// Romanizes a pronunciation in katakana, using `ー` for long vowels. `spelling` is the same
+/// reading as written, like the kana column of UniDic, and tells long vowels apart from the
+/// い of せい or きい, which is pronounced long but written as i. It is ignored if its morae
+/// don't line up with `pronunciation`
+pub fn kana_to_romaji(pronunciation: &str, spelling: &str, options: &RomajiOptions) -> String {
+    let pronounced = morae(pronunciation);
+    let spelled = Some(morae(spelling)).filter(|spelled| spelled.len() == pronounced.len());
+
+    let mut romaji = String::new();
+    let mut geminate = false;
+
+    for (i, mora) in pronounced.iter().enumerate() {
+        match hiragana_to_katakana_char(mora.chars().next().unwrap_or(' ')) {
+            'ッ' => {
+                geminate = true;
+                continue;
+            }
+            'ー' => {
+                let spelled = spelled.as_ref().map(|spelled| spelled[i]);
+                if matches!(spelled, Some("イ") | Some("い")) && romaji.ends_with(['e', 'i']) {
+                    romaji.push('i');
+                } else {
+                    lengthen(&mut romaji, options.long_vowels);
+                }
+                continue;
+            }
+            'ン' => {
+                romaji.push('n');
+                // 金曜 is kin'yō, not kinyō
+                let next = pronounced
+                    .get(i + 1)
+                    .and_then(|next| mora_romaji(next, options.system));
+                if next.is_some_and(|next| next.starts_with(['a', 'i', 'u', 'e', 'o', 'y'])) {
+                    romaji.push('\'');
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let mora = match mora_romaji(mora, options.system) {
+            Some(mora) => mora,
+            None => mora.to_string(),
+        };
+
+        if geminate {
+            geminate = false;
+            match mora.chars().next() {
+                // まっちゃ is matcha in Hepburn
+                Some('c') if options.system == RomajiSystem::Hepburn => romaji.push('t'),
+                Some(c) if c.is_ascii_alphabetic() && !"aiueo".contains(c) => romaji.push(c),
+                _ => {}
+            }
+        }
+
+        romaji.push_str(&mora);
+    }
+
+    romaji
+}
+
+/// Lengthens the vowel at the end of `romaji`
+fn lengthen(romaji: &mut String, style: LongVowels) {
+    let vowel = match romaji.chars().last() {
+        Some(vowel) if "aiueo".contains(vowel) => vowel,
+        _ => return,
+    };
+
+    let long = match style {
+        LongVowels::Double => {
+            romaji.push(vowel);
+            return;
+        }
+        LongVowels::Macron => match vowel {
+            'a' => 'ā',
+            'i' => 'ī',
+            'u' => 'ū',
+            'e' => 'ē',
+            _ => 'ō',
+        },
+        LongVowels::Circumflex => match vowel {
+            'a' => 'â',
+            'i' => 'î',
+            'u' => 'û',
+            'e' => 'ê',
+            _ => 'ô',
+        },
+    };
+
+    romaji.pop();
+    romaji.push(long);
+}
+
+impl<'dict, 'input> Morpheme<'dict, 'input> {
+    /// Romanizes the pronunciation of the morpheme with the long vowels common for `system`.
+    /// Particles are romanized as pronounced, so は becomes wa
+    pub fn romaji(&self, system: RomajiSystem) -> String {
+        self.romaji_with(&RomajiOptions::new(system))
+    }
+
+    /// Romanizes the pronunciation of the morpheme
+    pub fn romaji_with(&self, options: &RomajiOptions) -> String {
+        let (pronunciation, spelling) = self.romaji_source();
+        kana_to_romaji(pronunciation, spelling, options)
+    }
+
+    /// Returns the pronunciation and spelling to romanize. Morphemes without reading, like
+    /// unknown words, use their surface
+    fn romaji_source(&self) -> (&str, &str) {
+        match self.reading {
+            "" | "*" => (self.surface, self.surface),
+            reading => (reading, self.kana_reading()),
+        }
+    }
+}
+
+impl Parser {
+    /// Parses and romanizes `text`. See [`romanize`]
+    pub fn romaji(&self, text: &str, options: &RomajiOptions) -> String {
+        romanize(text, self.parse_lenient(text), options)
+    }
+}
+
+/// Romanizes `morphemes`, which have to be parsed from `text`, into a single line. Words, as
+/// grouped by [`group_words`], are separated by spaces and Japanese punctuation is replaced by
+/// its ASCII counterpart
+pub fn romanize(text: &str, morphemes: Vec<Morpheme>, options: &RomajiOptions) -> String {
+    let mut line = String::new();
+    // Set after opening brackets, which are followed by a word without a space
+    let mut attach_next = true;
+
+    for word in group_words(text, morphemes) {
+        if word.head().word_class == WordClass::Symbol {
+            let (symbol, opening) = match word.surface {
+                "。" => (".", false),
+                "、" => (",", false),
+                "！" => ("!", false),
+                "？" => ("?", false),
+                "「" | "『" => ("\"", true),
+                "」" | "』" => ("\"", false),
+                "（" => ("(", true),
+                "）" => (")", false),
+                "〜" | "～" => ("~", false),
+                "・" => (" ", true),
+                symbol => (symbol, false),
+            };
+
+            if opening && !attach_next && symbol != " " {
+                line.push(' ');
+            }
+            line.push_str(symbol);
+            attach_next = opening;
+            continue;
+        }
+
+        if word.head().word_class == WordClass::Space {
+            continue;
+        }
+
+        let mut pronunciation = String::new();
+        let mut spelling = String::new();
+        for morph in &word.morphemes {
+            let (morph_pronunciation, morph_spelling) = morph.romaji_source();
+            pronunciation.push_str(morph_pronunciation);
+            spelling.push_str(morph_spelling);
+        }
+
+        if !attach_next {
+            line.push(' ');
+        }
+        line.push_str(&kana_to_romaji(&pronunciation, &spelling, options));
+        attach_next = false;
+    }
+
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::morph;
+
+    fn romaji(pronunciation: &str, spelling: &str, system: RomajiSystem) -> String {
+        kana_to_romaji(pronunciation, spelling, &RomajiOptions::new(system))
+    }
+
+    fn hepburn(pronunciation: &str, spelling: &str) -> String {
+        romaji(pronunciation, spelling, RomajiSystem::Hepburn)
+    }
+
+    /// Returns the features of `surface`. `pos` holds the part of speech, cType and cForm
+    fn feature(pos: &str, surface: &str, pron: &str, kana: &str) -> String {
+        format!(
+            "{pos},{pron},{s},{s},{pron},{s},{pron},*,*,*,*,*,*,*,*,{kana},{kana},{kana},{kana},*,*,*,*,*",
+            pos = pos,
+            s = surface,
+            pron = pron,
+            kana = kana
+        )
+    }
+
+    #[test]
+    fn systems() {
+        assert_eq!(hepburn("シンブン", "シンブン"), "shinbun");
+        assert_eq!(
+            romaji("シンブン", "シンブン", RomajiSystem::Kunrei),
+            "sinbun"
+        );
+        assert_eq!(hepburn("ツヅク", "ツヅク"), "tsuzuku");
+        assert_eq!(romaji("ツヅク", "ツヅク", RomajiSystem::Kunrei), "tuzuku");
+        assert_eq!(
+            romaji("ツヅク", "ツヅク", RomajiSystem::NihonShiki),
+            "tuduku"
+        );
+        assert_eq!(hepburn("フジ", "フジ"), "fuji");
+        assert_eq!(romaji("フジ", "フジ", RomajiSystem::Kunrei), "huzi");
+    }
+
+    #[test]
+    fn sokuon() {
+        assert_eq!(hepburn("ガッコー", "ガッコウ"), "gakkō");
+        assert_eq!(hepburn("マッチャ", "マッチャ"), "matcha");
+        assert_eq!(
+            romaji("マッチャ", "マッチャ", RomajiSystem::Kunrei),
+            "mattya"
+        );
+        assert_eq!(hepburn("キップ", "キップ"), "kippu");
+    }
+
+    #[test]
+    fn long_vowels() {
+        let style = |long_vowels| RomajiOptions {
+            system: RomajiSystem::Hepburn,
+            long_vowels,
+        };
+
+        assert_eq!(hepburn("トーキョー", "トウキョウ"), "tōkyō");
+        assert_eq!(
+            romaji("トーキョー", "トウキョウ", RomajiSystem::Kunrei),
+            "tôkyô"
+        );
+        assert_eq!(
+            kana_to_romaji("トーキョー", "トウキョウ", &style(LongVowels::Double)),
+            "tookyoo"
+        );
+        assert_eq!(
+            kana_to_romaji("オカーサン", "オカアサン", &style(LongVowels::Circumflex)),
+            "okâsan"
+        );
+        // The い of せい and きい is written as i
+        assert_eq!(hepburn("セーネン", "セイネン"), "seinen");
+        assert_eq!(hepburn("ゲーム", "ゲーム"), "gēmu");
+    }
+
+    #[test]
+    fn syllabic_n() {
+        assert_eq!(hepburn("キンヨー", "キンヨウ"), "kin'yō");
+        assert_eq!(hepburn("ゲンイン", "ゲンイン"), "gen'in");
+        assert_eq!(hepburn("ホン", "ホン"), "hon");
+    }
+
+    #[test]
+    fn particles_are_romanized_as_pronounced() {
+        let text = "私は東京へ行きます。本を";
+        let words = [
+            (0, 3, "代名詞,*,*,*,*,*", "ワタクシ", "ワタクシ"),
+            (3, 6, "助詞,係助詞,*,*,*,*", "ワ", "ハ"),
+            (
+                6,
+                12,
+                "名詞,固有名詞,地名,一般,*,*",
+                "トーキョー",
+                "トウキョウ",
+            ),
+            (12, 15, "助詞,格助詞,*,*,*,*", "エ", "ヘ"),
+            (
+                15,
+                21,
+                "動詞,一般,*,*,五段-カ行,連用形-一般",
+                "イキ",
+                "イキ",
+            ),
+            (
+                21,
+                27,
+                "助動詞,*,*,*,助動詞-マス,終止形-一般",
+                "マス",
+                "マス",
+            ),
+            (27, 30, "補助記号,句点,*,*,*,*", "*", "*"),
+            (30, 33, "名詞,普通名詞,一般,*,*,*", "ホン", "ホン"),
+            (33, 36, "助詞,格助詞,*,*,*,*", "オ", "ヲ"),
+        ];
+        let features: Vec<String> = words
+            .iter()
+            .map(|&(start, end, pos, pron, kana)| feature(pos, &text[start..end], pron, kana))
+            .collect();
+        let morphemes: Vec<_> = words
+            .iter()
+            .zip(&features)
+            .map(|(&(start, end, ..), feature)| morph(&text[start..end], start, feature))
+            .collect();
+
+        assert_eq!(morphemes[1].romaji(RomajiSystem::Hepburn), "wa");
+        assert_eq!(morphemes[8].romaji(RomajiSystem::Hepburn), "o");
+        assert_eq!(
+            romanize(text, morphemes, &RomajiOptions::default()),
+            "watakushi wa tōkyō e ikimasu. hon o"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    byte_offset,
+    kana::{is_kana, katakana_to_hiragana},
+    Morpheme, Parser,
+};
+
+/// Markup used to annotate readings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RubyFormat {
+    /// `<ruby>漢字<rt>かんじ</rt></ruby>`. The input is emitted unescaped, so the output of
+    /// untrusted text must not be inserted into a page as HTML, as it allows cross site scripting
+    Html,
+    /// `漢字[かんじ]`, preceded by a space if the annotated text doesn't start the output or
+    /// follow whitespace, as Anki uses it to find the start of the annotated text
+    Anki,
+    /// `｜漢字《かんじ》`
+    Aozora,
+}
+
+/// Options for [`Parser::ruby`] and [`render_ruby`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RubyOptions {
+    pub format: RubyFormat,
+    /// Don't annotate tokens which are written in kana only, like katakana loanwords
+    pub skip_kana_only: bool,
+    /// Convert the katakana readings of the dictionary into hiragana
+    pub hiragana: bool,
+}
+
+impl Default for RubyOptions {
+    fn default() -> Self {
+        RubyOptions {
+            format: RubyFormat::Html,
+            skip_kana_only: true,
+            hiragana: true,
+        }
+    }
+}
+
+impl Parser {
+    /// Parses `text` and annotates the readings of its words. All text which isn't annotated is
+    /// kept exactly as it is, it is not escaped for any of the formats
+    pub fn ruby(&self, text: &str, options: &RubyOptions) -> String {
+        render_ruby(text, &self.parse_lenient(text), options)
+    }
+}
+
+/// Annotates the readings of `morphemes`, which have to be parsed from `text`. See
+/// [`Parser::ruby`]
+pub fn render_ruby(text: &str, morphemes: &[Morpheme], options: &RubyOptions) -> String {
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut pos = 0;
+
+    for morph in morphemes {
+        let start = byte_offset(text, morph.surface).unwrap_or(morph.start);
+        if start < pos {
+            continue;
+        }
+
+        out.push_str(&text[pos..start]);
+        pos = start + morph.surface.len();
+
+        if morph.word_class.is_symbol() || morph.word_class.is_space() {
+            out.push_str(morph.surface);
+            continue;
+        }
+
+        let segments = morph.furigana();
+        if segments.iter().any(|(_, reading)| reading.is_some()) {
+            for (segment, reading) in segments {
+                match reading {
+                    Some(reading) => annotate(&mut out, segment, reading, options),
+                    None => out.push_str(segment),
+                }
+            }
+            continue;
+        }
+
+        let kana_only = morph.surface.chars().all(is_kana);
+        let reading = morph.kana_reading();
+        if kana_only && !options.skip_kana_only && !reading.is_empty() {
+            annotate(&mut out, morph.surface, reading, options);
+        } else {
+            out.push_str(morph.surface);
+        }
+    }
+
+    out.push_str(&text[pos..]);
+    out
+}
+
+/// Writes `text` annotated with `reading` to `out`. Readings which equal the text are omitted
+fn annotate(out: &mut String, text: &str, reading: &str, options: &RubyOptions) {
+    let reading = if options.hiragana {
+        katakana_to_hiragana(reading)
+    } else {
+        reading.to_string()
+    };
+
+    if reading == text {
+        out.push_str(text);
+        return;
+    }
+
+    match options.format {
+        RubyFormat::Html => {
+            out.push_str("<ruby>");
+            out.push_str(text);
+            out.push_str("<rt>");
+            out.push_str(&reading);
+            out.push_str("</rt></ruby>");
+        }
+        RubyFormat::Anki => {
+            if out.chars().last().is_some_and(|c| !c.is_whitespace()) {
+                out.push(' ');
+            }
+            out.push_str(text);
+            out.push('[');
+            out.push_str(&reading);
+            out.push(']');
+        }
+        RubyFormat::Aozora => {
+            out.push('｜');
+            out.push_str(text);
+            out.push('《');
+            out.push_str(&reading);
+            out.push('》');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::morph;
+
+    /// Renders 猫が食べるテレビ。 with a space before 食べる if `space` is set
+    fn render(space: bool, options: RubyOptions) -> String {
+        let text = if space {
+            "猫が 食べるテレビ。"
+        } else {
+            "猫が食べるテレビ。"
+        };
+        let words = [
+            ("猫", "名詞,普通名詞,一般,*", "ネコ"),
+            ("が", "助詞,格助詞,*,*", "ガ"),
+            ("食べる", "動詞,一般,*,*", "タベル"),
+            ("テレビ", "名詞,普通名詞,一般,*", "テレビ"),
+            ("。", "補助記号,句点,*,*", "*"),
+        ];
+        let features: Vec<String> = words
+            .iter()
+            .map(|(_, pos, pron)| format!("{},*,*,*,*,*,{}", pos, pron))
+            .collect();
+        let morphemes: Vec<_> = words
+            .iter()
+            .zip(&features)
+            .map(|((surface, ..), feature)| morph(surface, text.find(surface).unwrap(), feature))
+            .collect();
+        render_ruby(text, &morphemes, &options)
+    }
+
+    fn options(format: RubyFormat) -> RubyOptions {
+        RubyOptions {
+            format,
+            ..RubyOptions::default()
+        }
+    }
+
+    #[test]
+    fn html() {
+        assert_eq!(
+            render(false, options(RubyFormat::Html)),
+            "<ruby>猫<rt>ねこ</rt></ruby>が<ruby>食<rt>た</rt></ruby>べるテレビ。"
+        );
+    }
+
+    #[test]
+    fn anki() {
+        // Annotations not starting the output or following whitespace get a leading space
+        assert_eq!(
+            render(false, options(RubyFormat::Anki)),
+            "猫[ねこ]が 食[た]べるテレビ。"
+        );
+        assert_eq!(
+            render(true, options(RubyFormat::Anki)),
+            "猫[ねこ]が 食[た]べるテレビ。"
+        );
+    }
+
+    #[test]
+    fn aozora() {
+        assert_eq!(
+            render(false, options(RubyFormat::Aozora)),
+            "｜猫《ねこ》が｜食《た》べるテレビ。"
+        );
+    }
+
+    #[test]
+    fn kana_only_words() {
+        let annotated = RubyOptions {
+            skip_kana_only: false,
+            ..options(RubyFormat::Aozora)
+        };
+        // が has the reading it is written in, so it stays unannotated
+        assert_eq!(
+            render(false, annotated),
+            "｜猫《ねこ》が｜食《た》べる｜テレビ《てれび》。"
+        );
+
+        let katakana = RubyOptions {
+            hiragana: false,
+            ..annotated
+        };
+        // Katakana readings only equal katakana words
+        assert_eq!(
+            render(false, katakana),
+            "｜猫《ネコ》｜が《ガ》｜食《タ》べるテレビ。"
+        );
+    }
+}
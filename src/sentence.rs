@@ -0,0 +1,311 @@
+use std::ops::Range;
+
+use crate::{byte_offset, Morpheme, ParseError, Parser};
+
+/// Characters ending a sentence
+const TERMINATORS: &[char] = &['。', '．', '！', '？', '!', '?', '…', '‥'];
+
+/// Brackets and quotes within which sentences don't end
+const OPENING: &[char] = &[
+    '「', '『', '（', '(', '【', '〈', '《', '［', '[', '｛', '{', '“', '‘',
+];
+const CLOSING: &[char] = &[
+    '」', '』', '）', ')', '】', '〉', '》', '］', ']', '｝', '}', '”', '’',
+];
+
+/// A sentence within the original text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SentenceSpan<'text> {
+    /// The sentence as it appears in the original text
+    pub text: &'text str,
+    /// Byte offset of the sentence within the original text
+    pub start: usize,
+}
+
+impl<'text> SentenceSpan<'text> {
+    /// Returns the byte offset right after the sentence
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    /// Returns the byte range of the sentence within the original text
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end()
+    }
+}
+
+/// A sentence together with its morphemes
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sentence<'dict, 'text> {
+    pub span: SentenceSpan<'text>,
+    pub morphemes: Vec<Morpheme<'dict, 'text>>,
+}
+
+impl Parser {
+    /// Splits `text` into sentences. Sentences end at newlines and at sentence ending symbols
+    /// like `。`, `！` or `？` which are not enclosed by brackets or quotes
+    pub fn sentences<'text>(&self, text: &'text str) -> Vec<SentenceSpan<'text>> {
+        split_sentences(text, self.parse_lenient(text))
+            .into_iter()
+            .map(|sentence| sentence.span)
+            .collect()
+    }
+
+    /// Parses `text` leniently and groups the morphemes by sentence. See [`Parser::sentences`]
+    /// for how sentences are detected
+    pub fn parse_sentences<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+    ) -> Vec<Sentence<'dict, 'text>> {
+        split_sentences(text, self.parse_lenient(text))
+    }
+
+    /// Same as [`Parser::parse_sentences`] but fails like [`Parser::try_parse`] on features
+    /// which can't be mapped
+    pub fn try_parse_sentences<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+    ) -> Result<Vec<Sentence<'dict, 'text>>, ParseError> {
+        Ok(split_sentences(text, self.try_parse(text)?))
+    }
+}
+
+/// Groups `morphemes`, which have to be parsed from `text`, by sentence. Whitespace containing
+/// a newline separates sentences and is not part of any of them
+pub fn split_sentences<'dict, 'text>(
+    text: &'text str,
+    morphemes: Vec<Morpheme<'dict, 'text>>,
+) -> Vec<Sentence<'dict, 'text>> {
+    let mut sentences = Vec::new();
+    let mut current: Vec<Morpheme> = Vec::new();
+    let mut prev_end = 0;
+    let mut depth = 0usize;
+
+    let mut iter = morphemes.into_iter().peekable();
+    while let Some(morph) = iter.next() {
+        let start = byte_offset(text, morph.surface).unwrap_or(morph.start);
+
+        // Newlines between two morphemes or within a whitespace morpheme
+        let newline_gap = text
+            .get(prev_end..start)
+            .is_some_and(|gap| gap.contains('\n'));
+        let newline_space = morph.word_class.is_space() && morph.surface.contains('\n');
+        prev_end = start + morph.surface.len();
+
+        if newline_gap || newline_space {
+            push_sentence(text, &mut sentences, &mut current);
+            depth = 0;
+            if newline_space {
+                continue;
+            }
+        }
+
+        if is_symbol_of(&morph, OPENING) {
+            depth += 1;
+        } else if is_symbol_of(&morph, CLOSING) {
+            depth = depth.saturating_sub(1);
+        }
+
+        let ends_sentence = depth == 0 && is_terminator(&morph);
+        current.push(morph);
+
+        // Keep runs of terminators like `！？` in the same sentence
+        if ends_sentence && !iter.peek().is_some_and(is_terminator) {
+            push_sentence(text, &mut sentences, &mut current);
+        }
+    }
+
+    push_sentence(text, &mut sentences, &mut current);
+    sentences
+}
+
+/// Moves the morphemes of `current` into a new sentence
+fn push_sentence<'dict, 'text>(
+    text: &'text str,
+    sentences: &mut Vec<Sentence<'dict, 'text>>,
+    current: &mut Vec<Morpheme<'dict, 'text>>,
+) {
+    let (first, last) = match (current.first(), current.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return,
+    };
+
+    let start = byte_offset(text, first.surface).unwrap_or(first.start);
+    let end = byte_offset(text, last.surface).unwrap_or(last.start) + last.surface.len();
+
+    sentences.push(Sentence {
+        span: SentenceSpan {
+            text: &text[start..end],
+            start,
+        },
+        morphemes: std::mem::take(current),
+    });
+}
+
+fn is_terminator(morph: &Morpheme) -> bool {
+    morph.word_class.is_symbol()
+        && (morph.features().pos2 == "句点"
+            || morph.surface.chars().all(|c| TERMINATORS.contains(&c)))
+}
+
+fn is_symbol_of(morph: &Morpheme, symbols: &[char]) -> bool {
+    morph.word_class.is_symbol() && morph.surface.chars().all(|c| symbols.contains(&c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RawMorpheme;
+
+    const NOUN: &str = "名詞,普通名詞,一般,*,*,*";
+    const PERIOD: &str = "補助記号,句点,*,*,*,*";
+    const SYMBOL: &str = "補助記号,一般,*,*,*,*";
+    const OPEN: &str = "補助記号,括弧開,*,*,*,*";
+    const CLOSE: &str = "補助記号,括弧閉,*,*,*,*";
+
+    /// Returns the raw morphemes of `pieces`, given as surface and features. Text between them
+    /// is skipped like igo skips whitespace
+    fn raw<'a>(text: &'a str, pieces: &[(&str, &'a str)]) -> Vec<RawMorpheme<'a, 'a>> {
+        let mut cursor = 0;
+        pieces
+            .iter()
+            .map(|&(surface, feature)| {
+                let start = cursor + text[cursor..].find(surface).unwrap();
+                cursor = start + surface.len();
+                RawMorpheme {
+                    surface: &text[start..cursor],
+                    feature,
+                    start,
+                }
+            })
+            .collect()
+    }
+
+    fn split<'a>(text: &'a str, pieces: &[(&str, &'a str)]) -> Vec<&'a str> {
+        let morphemes = raw(text, pieces)
+            .into_iter()
+            .map(Morpheme::from_raw_lenient)
+            .collect();
+        split_sentences(text, morphemes)
+            .iter()
+            .map(|sentence| sentence.span.text)
+            .collect()
+    }
+
+    #[test]
+    fn every_terminator_ends_a_sentence() {
+        for terminator in TERMINATORS {
+            let text = format!("猫{}犬", terminator);
+            let end = format!("猫{}", terminator);
+            let surface = terminator.to_string();
+            let sentences = split(&text, &[("猫", NOUN), (&surface, SYMBOL), ("犬", NOUN)]);
+            assert_eq!(sentences, vec![end.as_str(), "犬"], "{}", terminator);
+        }
+
+        // Runs of terminators stay together, 。 ends sentences by its part of speech too
+        let text = "猫！？犬……。";
+        let sentences = split(
+            text,
+            &[
+                ("猫", NOUN),
+                ("！", SYMBOL),
+                ("？", SYMBOL),
+                ("犬", NOUN),
+                ("……", SYMBOL),
+                ("。", PERIOD),
+            ],
+        );
+        assert_eq!(sentences, vec!["猫！？", "犬……。"]);
+    }
+
+    #[test]
+    fn quotes_and_brackets() {
+        let text = "「猫。犬。」と（鳥！）。次";
+        let sentences = split(
+            text,
+            &[
+                ("「", OPEN),
+                ("猫", NOUN),
+                ("。", PERIOD),
+                ("犬", NOUN),
+                ("。", PERIOD),
+                ("」", CLOSE),
+                ("と", "助詞,格助詞,*,*,*,*"),
+                ("（", OPEN),
+                ("鳥", NOUN),
+                ("！", SYMBOL),
+                ("）", CLOSE),
+                ("。", PERIOD),
+                ("次", NOUN),
+            ],
+        );
+        assert_eq!(sentences, vec!["「猫。犬。」と（鳥！）。", "次"]);
+
+        // A newline ends an unclosed quote
+        let text = "「猫\n犬。";
+        let sentences = split(
+            text,
+            &[("「", OPEN), ("猫", NOUN), ("犬", NOUN), ("。", PERIOD)],
+        );
+        assert_eq!(sentences, vec!["「猫", "犬。"]);
+    }
+
+    #[test]
+    fn newlines_and_trailing_text() {
+        // Newlines skipped between morphemes and newlines within whitespace morphemes
+        let text = "猫\n\n犬 \n鳥";
+        let sentences = split(
+            text,
+            &[
+                ("猫", NOUN),
+                ("犬", NOUN),
+                (" \n", "空白,*,*,*,*,*"),
+                ("鳥", NOUN),
+            ],
+        );
+        assert_eq!(sentences, vec!["猫", "犬", "鳥"]);
+
+        // Spaces without a newline don't split
+        assert_eq!(split("猫 犬", &[("猫", NOUN), ("犬", NOUN)]), vec!["猫 犬"]);
+
+        let text = "猫。犬と鳥";
+        let sentences = split(
+            text,
+            &[
+                ("猫", NOUN),
+                ("。", PERIOD),
+                ("犬", NOUN),
+                ("と", NOUN),
+                ("鳥", NOUN),
+            ],
+        );
+        assert_eq!(sentences, vec!["猫。", "犬と鳥"]);
+        assert_eq!(split("", &[]), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn strict_and_lenient_morphemes_split_alike() {
+        // try_parse_sentences and parse_sentences only differ in how features are mapped
+        let text = "「猫。」\n犬！";
+        let pieces = [
+            ("「", OPEN),
+            ("猫", NOUN),
+            ("。", PERIOD),
+            ("」", CLOSE),
+            ("犬", NOUN),
+            ("！", SYMBOL),
+        ];
+        let strict: Vec<_> = raw(text, &pieces)
+            .into_iter()
+            .map(|raw| Morpheme::try_from_raw(raw).unwrap())
+            .collect();
+        let lenient = raw(text, &pieces)
+            .into_iter()
+            .map(Morpheme::from_raw_lenient)
+            .collect();
+        assert_eq!(
+            split_sentences(text, strict),
+            split_sentences(text, lenient)
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use crate::{FeatureColumn, Morpheme};
+
+/// Counts morphemes whose features fell back to `Unknown` variants while parsing leniently
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnknownStats<'dict> {
+    tokens: usize,
+    unknown_tokens: usize,
+    values: HashMap<(FeatureColumn, &'dict str), usize>,
+}
+
+impl<'dict> UnknownStats<'dict> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a single morpheme
+    pub fn add(&mut self, morph: &Morpheme<'dict, '_>) {
+        self.tokens += 1;
+
+        let unknown = morph.unknown_features();
+        if unknown.is_empty() {
+            return;
+        }
+
+        self.unknown_tokens += 1;
+        for value in unknown {
+            *self.values.entry(value).or_default() += 1;
+        }
+    }
+
+    /// Records all morphemes of `morphs`
+    pub fn extend<'a, I>(&mut self, morphs: I)
+    where
+        I: IntoIterator<Item = &'a Morpheme<'dict, 'a>>,
+        'dict: 'a,
+    {
+        for morph in morphs {
+            self.add(morph);
+        }
+    }
+
+    /// Returns the amount of recorded morphemes
+    pub fn tokens(&self) -> usize {
+        self.tokens
+    }
+
+    /// Returns the amount of recorded morphemes which had at least one `Unknown` feature
+    pub fn unknown_tokens(&self) -> usize {
+        self.unknown_tokens
+    }
+
+    /// Returns how often each unmapped column value occurred
+    pub fn values(&self) -> &HashMap<(FeatureColumn, &'dict str), usize> {
+        &self.values
+    }
+
+    /// Returns the unmapped column values, the most frequent first. Ties are ordered by value and
+    /// then by column
+    pub fn sorted_values(&self) -> Vec<((FeatureColumn, &'dict str), usize)> {
+        let mut values: Vec<_> = self.values.iter().map(|(k, v)| (*k, *v)).collect();
+        values.sort_by(|((a_column, a_value), a), ((b_column, b_value), b)| {
+            b.cmp(a)
+                .then_with(|| a_value.cmp(b_value))
+                .then_with(|| a_column.index().cmp(&b_column.index()))
+        });
+        values
+    }
+
+    /// Adds the counts of `other` to `self`
+    pub fn merge(&mut self, other: &UnknownStats<'dict>) {
+        self.tokens += other.tokens;
+        self.unknown_tokens += other.unknown_tokens;
+        for (value, count) in &other.values {
+            *self.values.entry(*value).or_default() += count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::morph;
+
+    #[test]
+    fn sorted_values_orders_ties_by_column() {
+        let feature = "謎,*,*,*,*,謎";
+        let morpheme = morph("謎", 0, feature);
+
+        let mut stats = UnknownStats::new();
+        stats.add(&morpheme);
+        stats.add(&morpheme);
+
+        assert_eq!(stats.tokens(), 2);
+        assert_eq!(stats.unknown_tokens(), 2);
+        assert_eq!(
+            stats.sorted_values(),
+            vec![
+                ((FeatureColumn::Pos1, "謎"), 2),
+                ((FeatureColumn::CForm, "謎"), 2)
+            ]
+        );
+    }
+}
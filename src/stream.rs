@@ -0,0 +1,319 @@
+use std::{
+    io::{self, BufRead},
+    vec,
+};
+
+use crate::{byte_offset, Morpheme, MorphemeBuf, Parser};
+
+/// Chunks are cut at this size if they don't contain a line or sentence end before
+const MAX_CHUNK: usize = 1 << 16;
+
+/// UTF-8 encoded characters after which a chunk ends
+const CHUNK_ENDS: &[&[u8]] = &[b"\n", "。".as_bytes(), "！".as_bytes(), "？".as_bytes()];
+
+impl Parser {
+    /// Parses `text` lazily, one line or sentence at a time. Morphemes are parsed leniently, see
+    /// [`Parser::parse_lenient`]. Their `start` is the byte offset within the whole `text`
+    pub fn parse_iter<'text, 'dict>(&'dict self, text: &'text str) -> ParseIter<'dict, 'text> {
+        ParseIter {
+            parser: self,
+            text,
+            pos: 0,
+            current: Vec::new().into_iter(),
+        }
+    }
+
+    /// Parses the text of `reader` lazily, one line or sentence at a time, reading no more than
+    /// a chunk of it into memory. Morphemes are parsed leniently, see [`Parser::parse_lenient`].
+    /// Their `start` is the byte offset within the whole stream
+    pub fn parse_reader<R: BufRead>(&self, reader: R) -> ReaderIter<'_, R> {
+        ReaderIter {
+            parser: self,
+            chunks: Chunks::new(reader),
+            current: Vec::new().into_iter(),
+        }
+    }
+}
+
+/// Iterator returned by [`Parser::parse_iter`]
+pub struct ParseIter<'dict, 'text> {
+    parser: &'dict Parser,
+    text: &'text str,
+    pos: usize,
+    current: vec::IntoIter<Morpheme<'dict, 'text>>,
+}
+
+impl<'dict, 'text> Iterator for ParseIter<'dict, 'text> {
+    type Item = Morpheme<'dict, 'text>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(morph) = self.current.next() {
+                return Some(morph);
+            }
+
+            if self.pos >= self.text.len() {
+                return None;
+            }
+
+            let rest = &self.text[self.pos..];
+            let len = chunk_end(rest.as_bytes(), 0).unwrap_or_else(|| cut(rest.as_bytes()));
+            let chunk = &rest[..len];
+            self.pos += len;
+
+            let text = self.text;
+            let mut morphemes = self.parser.parse_lenient(chunk);
+            for morph in &mut morphemes {
+                morph.start = byte_offset(text, morph.surface).unwrap_or(morph.start);
+            }
+            self.current = morphemes.into_iter();
+        }
+    }
+}
+
+/// Iterator returned by [`Parser::parse_reader`]
+pub struct ReaderIter<'dict, R> {
+    parser: &'dict Parser,
+    chunks: Chunks<R>,
+    current: vec::IntoIter<MorphemeBuf>,
+}
+
+impl<'dict, R: BufRead> Iterator for ReaderIter<'dict, R> {
+    type Item = io::Result<MorphemeBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(morph) = self.current.next() {
+                return Some(Ok(morph));
+            }
+
+            let (offset, chunk) = match self.chunks.next_chunk() {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => return None,
+                Err(err) => {
+                    // Don't try to read the rest of a broken stream
+                    self.chunks.done = true;
+                    self.chunks.pending.clear();
+                    return Some(Err(err));
+                }
+            };
+
+            let morphemes: Vec<_> = self
+                .parser
+                .parse_lenient(&chunk)
+                .into_iter()
+                .map(|morph| {
+                    let start = byte_offset(&chunk, morph.surface).unwrap_or(morph.start);
+                    let mut morph = morph.to_buf();
+                    morph.start = offset + start;
+                    morph
+                })
+                .collect();
+            self.current = morphemes.into_iter();
+        }
+    }
+}
+
+/// Splits the text of a reader into chunks ending at a line or sentence end
+struct Chunks<R> {
+    reader: R,
+    /// Bytes read but not returned yet
+    pending: Vec<u8>,
+    /// Byte offset of `pending` within the stream
+    offset: usize,
+    /// Number of bytes at the start of `pending` known not to start a chunk end
+    searched: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Chunks<R> {
+    fn new(reader: R) -> Self {
+        Chunks {
+            reader,
+            pending: Vec::new(),
+            offset: 0,
+            searched: 0,
+            done: false,
+        }
+    }
+
+    /// Returns the next chunk of text along with its byte offset within the stream, or `None` at
+    /// the end of the stream
+    fn next_chunk(&mut self) -> io::Result<Option<(usize, String)>> {
+        loop {
+            let len = match chunk_end(&self.pending, self.searched) {
+                Some(end) => end,
+                None if self.pending.len() >= MAX_CHUNK => cut(&self.pending),
+                None if self.done && !self.pending.is_empty() => self.pending.len(),
+                None if self.done => return Ok(None),
+                None => {
+                    // A chunk end may start within the last two bytes and be completed by the
+                    // next read
+                    self.searched = self.pending.len().min(MAX_CHUNK).saturating_sub(2);
+                    let data = match self.reader.fill_buf() {
+                        Ok(data) => data,
+                        Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                        Err(err) => return Err(err),
+                    };
+                    if data.is_empty() {
+                        self.done = true;
+                    }
+
+                    let read = data.len();
+                    self.pending.extend_from_slice(data);
+                    self.reader.consume(read);
+                    continue;
+                }
+            };
+
+            self.searched = 0;
+            let rest = self.pending.split_off(len);
+            let chunk = std::mem::replace(&mut self.pending, rest);
+            let offset = self.offset;
+            self.offset += len;
+            return String::from_utf8(chunk)
+                .map(|chunk| Some((offset, chunk)))
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err));
+        }
+    }
+}
+
+/// Returns the length of the first chunk of `bytes` if it ends with a newline or sentence end
+/// within the chunk size. The search starts at `from`
+fn chunk_end(bytes: &[u8], from: usize) -> Option<usize> {
+    let limit = bytes.len().min(MAX_CHUNK);
+    (from..limit).find_map(|i| {
+        CHUNK_ENDS
+            .iter()
+            .find(|end| bytes[i..].starts_with(end))
+            .map(|end| i + end.len())
+    })
+}
+
+/// Returns the length of a chunk cut from `bytes` without a line or sentence end. The cut is
+/// made at the last character boundary within the chunk size
+fn cut(bytes: &[u8]) -> usize {
+    if bytes.len() <= MAX_CHUNK {
+        return bytes.len();
+    }
+
+    (1..=MAX_CHUNK)
+        .rev()
+        .find(|i| bytes[*i] & 0xC0 != 0x80)
+        .unwrap_or(MAX_CHUNK)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Read};
+
+    use super::*;
+
+    /// Returns all chunks of `reader` with their offsets
+    fn chunks<R: BufRead>(reader: R) -> io::Result<Vec<(usize, String)>> {
+        let mut chunks = Chunks::new(reader);
+        let mut all = Vec::new();
+        while let Some(chunk) = chunks.next_chunk()? {
+            all.push(chunk);
+        }
+        Ok(all)
+    }
+
+    #[test]
+    fn chunk_ends() {
+        assert_eq!(chunk_end(b"ab\ncd", 0), Some(3));
+        assert_eq!(chunk_end("東京。次".as_bytes(), 0), Some(9));
+        assert_eq!(chunk_end("はい！次".as_bytes(), 0), Some(9));
+        assert_eq!(chunk_end("え？".as_bytes(), 0), Some(6));
+        // 、 shares its first two bytes with 。
+        assert_eq!(chunk_end("東京、次".as_bytes(), 0), None);
+        assert_eq!(chunk_end(b"abc", 0), None);
+        assert_eq!(chunk_end(b"", 0), None);
+        assert_eq!(chunk_end(b"a\nb\n", 2), Some(4));
+
+        // Ends after the chunk size don't count
+        let mut long = vec![b'a'; MAX_CHUNK];
+        long.push(b'\n');
+        assert_eq!(chunk_end(&long, 0), None);
+        long[MAX_CHUNK - 1] = b'\n';
+        assert_eq!(chunk_end(&long, 0), Some(MAX_CHUNK));
+    }
+
+    #[test]
+    fn cuts_at_character_boundaries() {
+        assert_eq!(cut(b"abc"), 3);
+        assert_eq!(cut(&vec![b'a'; MAX_CHUNK + 1]), MAX_CHUNK);
+
+        // MAX_CHUNK isn't a multiple of 3, so the cut is made before the split character
+        let kana = "あ".repeat(MAX_CHUNK);
+        let len = cut(kana.as_bytes());
+        assert_eq!(len, MAX_CHUNK / 3 * 3);
+        assert!(kana.is_char_boundary(len));
+    }
+
+    #[test]
+    fn reader_chunks_and_offsets() {
+        // A small buffer splits characters and chunk ends between reads
+        let text = "東京に。\nはい！え？残り";
+        let reader = BufReader::with_capacity(4, text.as_bytes());
+        assert_eq!(
+            chunks(reader).unwrap(),
+            vec![
+                (0, "東京に。".to_string()),
+                (12, "\n".to_string()),
+                (13, "はい！".to_string()),
+                (22, "え？".to_string()),
+                (28, "残り".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reader_cuts_long_chunks() {
+        let text = format!("{}。東京", "あ".repeat(MAX_CHUNK / 2));
+        let reader = BufReader::with_capacity(7, text.as_bytes());
+        let chunks = chunks(reader).unwrap();
+
+        let cut = MAX_CHUNK / 3 * 3;
+        assert_eq!(chunks.len(), 3);
+        assert_eq!((chunks[0].0, chunks[0].1.len()), (0, cut));
+        assert_eq!(chunks[1].0, cut);
+        assert!(chunks[1].1.ends_with('。'));
+        assert_eq!(chunks[2], (text.len() - 6, "東京".to_string()));
+    }
+
+    /// Returns at most 5 bytes per read and fails with `Interrupted` before every read
+    struct Interrupting<'a> {
+        data: &'a [u8],
+        interrupt: bool,
+    }
+
+    impl Read for Interrupting<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+
+            let len = buf.len().min(self.data.len()).min(5);
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn reader_retries_interrupted_reads() {
+        let reader = BufReader::new(Interrupting {
+            data: "東京に。東京都".as_bytes(),
+            interrupt: false,
+        });
+        assert_eq!(
+            chunks(reader).unwrap(),
+            vec![(0, "東京に。".to_string()), (12, "東京都".to_string())]
+        );
+
+        let err = chunks(&b"ab\xFF\n"[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
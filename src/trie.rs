@@ -0,0 +1,402 @@
+//! The double array trie igo uses to look up surfaces, in igo's `word2id` layout.
+//!
+//! A child of the node with base `b` reached by the code unit `c` is at index `b + c` if its check
+//! is `c`. A non negative base is the base of the child, a negative base `-id - 1` ends the key
+//! `id`, whose remaining code units are `tail[begs[id]..begs[id] + lens[id]]`. Keys ending where
+//! others continue have a child with [`TERMINATE_CODE`]. The root's base is stored at index 0.
+//!
+//! The trie is stored as the number of nodes, keys and tail code units followed by the arrays
+//! `begs`, `base`, `lens`, `chck` and `tail`, all little endian.
+
+use std::convert::TryFrom;
+
+use crate::dictionary::{i16_at, i32_at, u16_at};
+
+/// Code marking the end of a key in the trie
+pub(crate) const TERMINATE_CODE: u16 = 0;
+/// Check value of unused slots of the trie. U+FFFF is not a character, so it never matches
+const VACANT_CODE: u16 = 0xFFFF;
+/// Number of UTF-16 code units. igo looks up children by adding the code unit to the base of a
+/// node, so the trie is padded by this after the highest base
+pub(crate) const CODE_LIMIT: usize = 0x10000;
+
+/// Size of the header holding the sizes of the arrays
+const HEADER: usize = 12;
+
+/// A trie stored in `word2id` layout
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Trie<'a> {
+    begs: &'a [u8],
+    base: &'a [u8],
+    lens: &'a [u8],
+    chck: &'a [u8],
+    tail: &'a [u8],
+}
+
+impl<'a> Trie<'a> {
+    /// Splits `bytes` into the arrays of the trie. Returns `None` if the sizes in the header
+    /// don't match the length of `bytes`
+    pub(crate) fn new(bytes: &'a [u8]) -> Option<Self> {
+        if bytes.len() < HEADER {
+            return None;
+        }
+        let size = |i| usize::try_from(i32_at(bytes, i)).ok();
+        let (nodes, keys, tail) = (size(0)?, size(1)?, size(2)?);
+        if nodes == 0 || bytes.len() != HEADER + keys * 6 + nodes * 6 + tail * 2 {
+            return None;
+        }
+
+        let (begs, rest) = bytes[HEADER..].split_at(keys * 4);
+        let (base, rest) = rest.split_at(nodes * 4);
+        let (lens, rest) = rest.split_at(keys * 2);
+        let (chck, tail) = rest.split_at(nodes * 2);
+        Some(Trie {
+            begs,
+            base,
+            lens,
+            chck,
+            tail,
+        })
+    }
+
+    /// Returns the number of keys
+    pub(crate) fn keys(&self) -> usize {
+        self.begs.len() / 4
+    }
+
+    /// Calls `f` with the length and id of each key which is a prefix of `text`, shortest first
+    pub(crate) fn common_prefix(&self, text: &[u16], mut f: impl FnMut(usize, usize)) {
+        let mut node = self.base(0);
+        let mut offset = 0;
+
+        while let Some(base) = node.and_then(|node| usize::try_from(node).ok()) {
+            if let Some(id) = self
+                .child(base, TERMINATE_CODE)
+                .and_then(|i| self.key_id(i))
+            {
+                f(offset, id);
+            }
+
+            let index = match text.get(offset) {
+                Some(&code) => match self.child(base, code) {
+                    Some(index) => index,
+                    None => return,
+                },
+                None => return,
+            };
+
+            node = self.base(index);
+            if let Some(id) = self.key_id(index) {
+                let rest = &text[offset + 1..];
+                if let Some(tail) = self.tail(id) {
+                    let len = tail.len() / 2;
+                    let units = tail
+                        .chunks_exact(2)
+                        .map(|u| u16::from_le_bytes([u[0], u[1]]));
+                    if rest.len() >= len && units.eq(rest[..len].iter().copied()) {
+                        f(offset + 1 + len, id);
+                    }
+                }
+                return;
+            }
+            offset += 1;
+        }
+    }
+
+    /// Returns the id of `key`
+    pub(crate) fn find(&self, key: &[u16]) -> Option<usize> {
+        let mut found = None;
+        self.common_prefix(key, |len, id| {
+            if len == key.len() {
+                found = Some(id);
+            }
+        });
+        found
+    }
+
+    fn base(&self, index: usize) -> Option<i32> {
+        (index < self.base.len() / 4).then(|| i32_at(self.base, index))
+    }
+
+    /// Returns the index of the child of the node with `base` reached by `code`
+    fn child(&self, base: usize, code: u16) -> Option<usize> {
+        let index = base + code as usize;
+        (index < self.chck.len() / 2 && u16_at(self.chck, index) == code).then_some(index)
+    }
+
+    /// Returns the id of the key ending at `index`
+    fn key_id(&self, index: usize) -> Option<usize> {
+        let base = self.base(index)?;
+        if base >= 0 {
+            return None;
+        }
+        let id = (-(base as i64) - 1) as usize;
+        (id < self.keys()).then_some(id)
+    }
+
+    /// Returns the code units of the key `id` which are not part of the double array
+    fn tail(&self, id: usize) -> Option<&'a [u8]> {
+        let start = usize::try_from(i32_at(self.begs, id)).ok()?;
+        let len = usize::try_from(i16_at(self.lens, id)).ok()?;
+        self.tail.get(start * 2..(start + len) * 2)
+    }
+}
+
+/// Returns the trie of `keys` in `word2id` layout. `keys` have to be sorted and distinct, the id
+/// of each key is its index
+pub(crate) fn build(keys: &[Vec<u16>]) -> Vec<u8> {
+    let mut builder = TrieBuilder {
+        keys,
+        base: vec![0],
+        chck: vec![VACANT_CODE],
+        begs: vec![0; keys.len()],
+        lens: vec![0; keys.len()],
+        tail: Vec::new(),
+        free: vec![1],
+        free_bases: vec![1],
+        starts: Vec::new(),
+    };
+    builder.insert(0, 0, keys.len(), 0);
+
+    // Every used index is below the highest base plus the highest code unit
+    let max_base = builder.base.iter().copied().max().unwrap_or_default() as usize;
+    let size = max_base + CODE_LIMIT;
+    builder.base.resize(size, 0);
+    builder.chck.resize(size, VACANT_CODE);
+
+    let mut bytes = Vec::with_capacity(HEADER + keys.len() * 6 + size * 6 + builder.tail.len() * 2);
+    for size in [size, keys.len(), builder.tail.len()] {
+        bytes.extend_from_slice(&(size as i32).to_le_bytes());
+    }
+    for value in builder.begs.iter().chain(&builder.base) {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    for len in &builder.lens {
+        bytes.extend_from_slice(&len.to_le_bytes());
+    }
+    for unit in builder.chck.iter().chain(&builder.tail) {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+    bytes
+}
+
+struct TrieBuilder<'k> {
+    keys: &'k [Vec<u16>],
+    base: Vec<i32>,
+    chck: Vec<u16>,
+    begs: Vec<i32>,
+    lens: Vec<i16>,
+    tail: Vec<u16>,
+    /// For each index a lower bound of the first unused index at or after it. Unused indices
+    /// point to themselves
+    free: Vec<usize>,
+    /// The same for the bases assigned to a node. Checks only hold the code unit and not the
+    /// parent, so two nodes sharing a base would find each other's children
+    free_bases: Vec<usize>,
+    /// Index of the first child of the last node placed, by the bit length of its number of
+    /// children
+    starts: Vec<usize>,
+}
+
+impl TrieBuilder<'_> {
+    /// Sets the base at `index` for the node of the keys `start..end`, which share their first
+    /// `depth` code units
+    fn insert(&mut self, index: usize, start: usize, end: usize, depth: usize) {
+        if end - start == 1 && index != 0 {
+            let key = &self.keys[start];
+            let rest = &key[depth.min(key.len())..];
+            self.begs[start] = self.tail.len() as i32;
+            self.lens[start] = rest.len() as i16;
+            self.tail.extend_from_slice(rest);
+            self.base[index] = -(start as i32) - 1;
+            return;
+        }
+
+        let code = |key: &Vec<u16>| key.get(depth).copied().unwrap_or(TERMINATE_CODE);
+        let mut children: Vec<(u16, usize, usize)> = Vec::new();
+        for i in start..end {
+            let c = code(&self.keys[i]);
+            match children.last_mut() {
+                Some(last) if last.0 == c => last.2 = i + 1,
+                _ => children.push((c, i, i + 1)),
+            }
+        }
+
+        let base = self.find_base(&children);
+        self.base[index] = base as i32;
+        self.free_bases[base] = base + 1;
+        for (c, _, _) in &children {
+            let child = base + *c as usize;
+            self.chck[child] = *c;
+            self.free[child] = child + 1;
+        }
+        for (c, start, end) in children {
+            self.insert(base + c as usize, start, end, depth + 1);
+        }
+    }
+
+    /// Returns an unused base for which the indices of all `children` are unused
+    fn find_base(&mut self, children: &[(u16, usize, usize)]) -> usize {
+        let first = children.first().map_or(0, |child| child.0 as usize);
+        let last = children.last().map_or(0, |child| child.0 as usize);
+
+        // Indices only get used, so a node doesn't fit before the previous one with about as many
+        // children either. Single children fit into any gap, so they start at the beginning
+        let size = (usize::BITS - children.len().leading_zeros()) as usize;
+        if size >= self.starts.len() {
+            self.starts.resize(size + 1, 0);
+        }
+        let start = if children.len() > 1 {
+            self.starts[size]
+        } else {
+            0
+        };
+        let mut base = start.max(first + 1) - first;
+
+        loop {
+            base = next_unused(&mut self.free_bases, base);
+            let pos = next_unused(&mut self.free, base + first);
+            if pos != base + first {
+                base = pos - first;
+                continue;
+            }
+
+            self.reserve(base + last);
+            let free = &self.free;
+            let taken = children.iter().map(|child| child.0 as usize).find(|c| {
+                let index = base + c;
+                free[index] != index
+            });
+            match taken {
+                // Move the child onto the next unused index
+                Some(c) => base = next_unused(&mut self.free, base + c) - c,
+                None => break,
+            }
+        }
+
+        self.starts[size] = base + first;
+        base
+    }
+
+    /// Grows the arrays to contain `index`
+    fn reserve(&mut self, index: usize) {
+        if index >= self.base.len() {
+            let size = (index + 1).max(self.base.len() * 2);
+            self.base.resize(size, 0);
+            self.chck.resize(size, VACANT_CODE);
+            grow(&mut self.free, size);
+            grow(&mut self.free_bases, size);
+        }
+    }
+}
+
+/// Returns the first index at or after `index` which points to itself in `links`, making the
+/// indices passed point to it
+fn next_unused(links: &mut Vec<usize>, index: usize) -> usize {
+    let mut unused = index;
+    loop {
+        grow(links, unused + 1);
+        if links[unused] == unused {
+            break;
+        }
+        unused = links[unused];
+    }
+
+    let mut i = index;
+    while i != unused {
+        i = std::mem::replace(&mut links[i], unused);
+    }
+    unused
+}
+
+/// Grows `links` to at least `size` unused indices
+fn grow(links: &mut Vec<usize>, size: usize) {
+    let len = links.len();
+    if size > len {
+        links.extend(len..size.max(len * 2));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(words: &[&str]) -> Vec<Vec<u16>> {
+        let mut keys: Vec<Vec<u16>> = words.iter().map(|w| w.encode_utf16().collect()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    fn utf16(text: &str) -> Vec<u16> {
+        text.encode_utf16().collect()
+    }
+
+    fn prefixes(trie: &Trie, text: &[u16]) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        trie.common_prefix(text, |len, id| found.push((len, id)));
+        found
+    }
+
+    #[test]
+    fn prefixes_of_text() {
+        let keys = keys(&["a", "ab", "abc", "b", "東京", "東京都", "都"]);
+        let bytes = build(&keys);
+        let trie = Trie::new(&bytes).unwrap();
+        let id = |key: &str| keys.iter().position(|k| *k == utf16(key)).unwrap();
+
+        assert_eq!(trie.keys(), keys.len());
+        assert_eq!(
+            prefixes(&trie, &utf16("abcd")),
+            vec![(1, id("a")), (2, id("ab")), (3, id("abc"))]
+        );
+        assert_eq!(
+            prefixes(&trie, &utf16("東京都庁")),
+            vec![(2, id("東京")), (3, id("東京都"))]
+        );
+        assert_eq!(prefixes(&trie, &utf16("東")), vec![]);
+        assert_eq!(prefixes(&trie, &utf16("x")), vec![]);
+        assert_eq!(trie.find(&utf16("東京")), Some(id("東京")));
+        assert_eq!(trie.find(&utf16("東")), None);
+        assert!(bytes.len() >= CODE_LIMIT * 6);
+    }
+
+    #[test]
+    fn all_keys_are_found() {
+        let kana: Vec<char> = "あいうえおかきくけこアイウエオ日本語".chars().collect();
+        let mut words = Vec::new();
+        for a in &kana {
+            words.push(a.to_string());
+            for b in &kana {
+                words.push(format!("{}{}", a, b));
+                words.push(format!("{}{}{}", a, b, a));
+            }
+        }
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let keys = keys(&words);
+        let bytes = build(&keys);
+        let trie = Trie::new(&bytes).unwrap();
+
+        for (id, key) in keys.iter().enumerate() {
+            let found = prefixes(&trie, key);
+            let expected: Vec<(usize, usize)> = (1..=key.len())
+                .filter_map(|len| {
+                    let prefix = keys.binary_search(&key[..len].to_vec()).ok()?;
+                    Some((len, prefix))
+                })
+                .collect();
+            assert_eq!(found, expected);
+            assert_eq!(found.last(), Some(&(key.len(), id)));
+        }
+    }
+
+    #[test]
+    fn empty_and_invalid_tries() {
+        let bytes = build(&[]);
+        let trie = Trie::new(&bytes).unwrap();
+        assert_eq!(prefixes(&trie, &utf16("a")), vec![]);
+
+        assert!(Trie::new(&bytes[..bytes.len() - 1]).is_none());
+        assert!(Trie::new(&[0; 8]).is_none());
+    }
+}
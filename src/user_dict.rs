@@ -0,0 +1,713 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    byte_offset, dictionary::Dictionary, features::split_features, kana::hiragana_to_katakana_char,
+    lattice::Lattice, trie, trie::Trie, unknown_error, FeatureColumn, FeatureError, Morpheme,
+    Parser, RawMorpheme, UserDictionaryError, WordClass,
+};
+
+/// Number of part of speech columns at the start of the features
+const POS_COLUMNS: usize = 4;
+
+/// Additional words taking part in the segmentation next to the system dictionary.
+///
+/// The entries are added to the lattice of the system dictionary, so they compete with its words
+/// on the same terms: the path through the text with the lowest sum of word costs and connection
+/// costs between neighboring words is picked. Each entry has left and right context ids of the
+/// system dictionary's matrix, either given directly in the UniDic `lex.csv` layout or taken from
+/// the system words of its word class.
+#[derive(Clone, Debug, Default)]
+pub struct UserDictionary {
+    entries: Vec<Entry>,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    surface: String,
+    kind: EntryKind,
+}
+
+#[derive(Clone, Debug)]
+enum EntryKind {
+    /// A word with context ids of the system dictionary's matrix and a word cost
+    Ids {
+        left_id: i16,
+        right_id: i16,
+        cost: i16,
+        feature: String,
+    },
+    /// A word taking the context ids of the system words of its word class, which are up to four
+    /// part of speech levels. Without a cost, the median cost of those words is used. The part
+    /// of speech columns of `columns` are replaced by the ones of the system words
+    WordClass {
+        levels: Vec<String>,
+        cost: Option<i16>,
+        columns: Vec<String>,
+    },
+}
+
+impl UserDictionary {
+    /// Creates an empty user dictionary
+    pub fn new() -> Self {
+        UserDictionary {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Loads a user dictionary from a CSV file. See [`UserDictionary::from_reader`]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, UserDictionaryError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads a user dictionary in CSV format. Each line is either in the UniDic `lex.csv`
+    /// layout `surface,left id,right id,cost,features...` or in the simplified layout
+    /// `surface,reading,word class[,cost]` of [`UserDictionary::add_word`]. Empty lines and
+    /// lines starting with `#` are skipped.
+    ///
+    /// The context ids and the cost of the `lex.csv` layout are the ones of the system
+    /// dictionary. If both ids are `*` or empty, they are taken from the system words with the
+    /// same four part of speech columns like for the simplified layout, and the cost may be left
+    /// out as well
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, UserDictionaryError> {
+        let mut dictionary = Self::new();
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line_nr = i + 1;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let columns = split_csv(&line);
+            let cost = |value: &str| {
+                value.trim().parse().map_err(|_| UserDictionaryError::Cost {
+                    line: line_nr,
+                    value: value.to_string(),
+                })
+            };
+            let context_id = |value: &str| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|_| UserDictionaryError::ContextId {
+                        line: line_nr,
+                        value: value.to_string(),
+                    })
+            };
+            let is_empty = |value: &str| matches!(value.trim(), "" | "*");
+
+            let added = match columns.len() {
+                3 => dictionary.add_word(&columns[0], &columns[1], &columns[2], None),
+                4 => {
+                    let cost = cost(&columns[3])?;
+                    dictionary.add_word(&columns[0], &columns[1], &columns[2], Some(cost))
+                }
+                count if count >= 8 && is_empty(&columns[1]) && is_empty(&columns[2]) => {
+                    let cost = match columns[3].as_str() {
+                        value if is_empty(value) => None,
+                        value => Some(cost(value)?),
+                    };
+                    dictionary.add_lex_word(&columns[0], cost, &columns[4..])
+                }
+                count if count >= 8 => {
+                    let (left_id, right_id) = (context_id(&columns[1])?, context_id(&columns[2])?);
+                    let cost = cost(&columns[3])?;
+                    let feature = join_features(&columns[4..]);
+                    dictionary.add_unidic(&columns[0], left_id, right_id, cost, &feature)
+                }
+                count => {
+                    return Err(UserDictionaryError::Columns {
+                        line: line_nr,
+                        count,
+                    })
+                }
+            };
+
+            added.map_err(|source| UserDictionaryError::Feature {
+                line: line_nr,
+                source,
+            })?;
+        }
+
+        Ok(dictionary)
+    }
+
+    /// Adds a word in the simplified format. `reading` may be written in hiragana or katakana
+    /// and `word_class` are one to four UniDic part of speech levels joined by `-`, like `名詞` or
+    /// `名詞-固有名詞-一般`. The word gets the context ids most system words starting with these
+    /// levels have, along with their remaining levels. Without a `cost`, the median cost of
+    /// those words is used.
+    ///
+    /// Words which conjungate need their conjungation kind and have to be added with
+    /// [`UserDictionary::add_unidic`]. Values containing a `"` directly followed by a `,` can't
+    /// be stored in a feature string and are rejected. Words with an empty surface are ignored
+    pub fn add_word(
+        &mut self,
+        surface: &str,
+        reading: &str,
+        word_class: &str,
+        cost: Option<i16>,
+    ) -> Result<(), FeatureError> {
+        for (column, value) in &[
+            (FeatureColumn::Orth, surface),
+            (FeatureColumn::Kana, reading),
+            (FeatureColumn::Pos1, word_class),
+        ] {
+            if value.contains("\",") {
+                return Err(FeatureError::unknown(*column, value));
+            }
+        }
+
+        let levels: Vec<String> = word_class
+            .split('-')
+            .take(POS_COLUMNS)
+            .map(str::to_string)
+            .collect();
+        let known = WordClass::from_features_lenient(&[levels[0].as_str()]);
+        if matches!(known, WordClass::Unknown(_))
+            || levels
+                .iter()
+                .any(|level| level.is_empty() || level.contains(','))
+        {
+            return Err(FeatureError::unknown(FeatureColumn::Pos1, word_class));
+        }
+
+        let reading: String = reading.chars().map(hiragana_to_katakana_char).collect();
+
+        let mut columns = vec!["*".to_string(); FeatureColumn::ALL.len()];
+        for column in &[
+            FeatureColumn::Lemma,
+            FeatureColumn::Orth,
+            FeatureColumn::OrthBase,
+        ] {
+            columns[column.index()] = surface.to_string();
+        }
+        for column in &[
+            FeatureColumn::LForm,
+            FeatureColumn::Pron,
+            FeatureColumn::PronBase,
+            FeatureColumn::Kana,
+            FeatureColumn::KanaBase,
+            FeatureColumn::Form,
+            FeatureColumn::FormBase,
+        ] {
+            columns[column.index()] = reading.clone();
+        }
+
+        self.push(
+            surface,
+            EntryKind::WordClass {
+                levels,
+                cost,
+                columns,
+            },
+        );
+        Ok(())
+    }
+
+    /// Adds a word with context ids and a word cost of the system dictionary and its raw, comma
+    /// separated UniDic feature string. Columns containing a comma have to be quoted with `"`.
+    /// Words with an empty surface are ignored
+    pub fn add_unidic(
+        &mut self,
+        surface: &str,
+        left_id: i16,
+        right_id: i16,
+        cost: i16,
+        feature: &str,
+    ) -> Result<(), FeatureError> {
+        check_features(surface, feature)?;
+        self.push(
+            surface,
+            EntryKind::Ids {
+                left_id,
+                right_id,
+                cost,
+                feature: feature.to_string(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Adds a word of the `lex.csv` layout without context ids, with its feature `columns`
+    fn add_lex_word(
+        &mut self,
+        surface: &str,
+        cost: Option<i16>,
+        columns: &[String],
+    ) -> Result<(), FeatureError> {
+        check_features(surface, &join_features(columns))?;
+        self.push(
+            surface,
+            EntryKind::WordClass {
+                levels: columns[..POS_COLUMNS].to_vec(),
+                cost,
+                columns: columns.to_vec(),
+            },
+        );
+        Ok(())
+    }
+
+    fn push(&mut self, surface: &str, kind: EntryKind) {
+        // Keys of the trie end with a NUL, so surfaces can't contain one
+        if !surface.is_empty() && !surface.contains('\0') {
+            self.entries.push(Entry {
+                surface: surface.to_string(),
+                kind,
+            });
+        }
+    }
+
+    /// Returns the amount of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the dictionary has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Only accepts entries the typed API can represent, so `Parser::parse` never panics on them
+fn check_features(surface: &str, feature: &str) -> Result<(), FeatureError> {
+    let features = split_features(feature);
+    let morph = Morpheme::from_features(surface, 0, feature, &features);
+    match morph.unknown_features().first() {
+        Some(&unknown) => Err(unknown_error(&features, unknown)),
+        None => Ok(()),
+    }
+}
+
+/// The entries of a [`UserDictionary`] with the context ids and costs of a system dictionary
+#[derive(Debug)]
+pub(crate) struct UserWords {
+    source: UserDictionary,
+    /// The trie of all surfaces in `word2id` layout
+    trie: Vec<u8>,
+    /// For each key id, the index of its first word, followed by the number of words
+    keys: Vec<usize>,
+    words: Vec<UserWord>,
+}
+
+/// An entry of a [`UserDictionary`] as it is added to the lattice
+#[derive(Debug)]
+pub(crate) struct UserWord {
+    pub(crate) left_id: i16,
+    pub(crate) right_id: i16,
+    pub(crate) cost: i16,
+    pub(crate) feature: String,
+}
+
+/// The part of speech, context ids and median cost most system words of a word class have
+struct Template {
+    pos: Vec<String>,
+    left_id: i16,
+    right_id: i16,
+    cost: i16,
+}
+
+impl UserWords {
+    fn new(dictionary: &Dictionary, source: UserDictionary) -> Result<Self, UserDictionaryError> {
+        let templates = templates(dictionary, &source.entries);
+        let (left_ids, right_ids) = dictionary.context_ids();
+
+        let mut words = Vec::with_capacity(source.entries.len());
+        for entry in &source.entries {
+            let word = match &entry.kind {
+                EntryKind::Ids {
+                    left_id,
+                    right_id,
+                    cost,
+                    feature,
+                } => {
+                    for (id, limit) in [(*left_id, left_ids), (*right_id, right_ids)] {
+                        if id < 0 || id as usize >= limit {
+                            return Err(UserDictionaryError::UnknownContextId {
+                                surface: entry.surface.clone(),
+                                id,
+                                limit,
+                            });
+                        }
+                    }
+                    UserWord {
+                        left_id: *left_id,
+                        right_id: *right_id,
+                        cost: *cost,
+                        feature: feature.clone(),
+                    }
+                }
+                EntryKind::WordClass {
+                    levels,
+                    cost,
+                    columns,
+                } => {
+                    let error = || UserDictionaryError::WordClass {
+                        surface: entry.surface.clone(),
+                        word_class: levels.join("-"),
+                    };
+                    let template = templates.get(levels).ok_or_else(error)?;
+
+                    let mut columns = columns.clone();
+                    columns[..POS_COLUMNS].clone_from_slice(&template.pos);
+                    let feature = join_features(&columns);
+                    check_features(&entry.surface, &feature).map_err(|_| error())?;
+
+                    UserWord {
+                        left_id: template.left_id,
+                        right_id: template.right_id,
+                        cost: cost.unwrap_or(template.cost),
+                        feature,
+                    }
+                }
+            };
+            words.push((entry.surface.encode_utf16().collect::<Vec<u16>>(), word));
+        }
+
+        // Words of the same surface keep the order they were added in
+        words.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut surfaces: Vec<Vec<u16>> = Vec::new();
+        let mut keys = Vec::new();
+        for (i, (surface, _)) in words.iter().enumerate() {
+            if surfaces.last() != Some(surface) {
+                surfaces.push(surface.clone());
+                keys.push(i);
+            }
+        }
+        keys.push(words.len());
+
+        Ok(UserWords {
+            source,
+            trie: trie::build(&surfaces),
+            keys,
+            words: words.into_iter().map(|(_, word)| word).collect(),
+        })
+    }
+
+    /// Calls `f` with the length and index of each word whose surface is a prefix of `text`
+    pub(crate) fn common_prefix(&self, text: &[u16], mut f: impl FnMut(usize, usize)) {
+        let trie = Trie::new(&self.trie).expect("the trie is built by `UserWords::new`");
+        trie.common_prefix(text, |len, id| {
+            for word in self.keys[id]..self.keys[id + 1] {
+                f(len, word);
+            }
+        });
+    }
+
+    pub(crate) fn word(&self, index: usize) -> &UserWord {
+        &self.words[index]
+    }
+}
+
+/// Returns the templates of the word classes of all `entries` which have system words
+fn templates(dictionary: &Dictionary, entries: &[Entry]) -> HashMap<Vec<String>, Template> {
+    let mut classes: Vec<&Vec<String>> = entries
+        .iter()
+        .filter_map(|entry| match &entry.kind {
+            EntryKind::WordClass { levels, .. } => Some(levels),
+            EntryKind::Ids { .. } => None,
+        })
+        .collect();
+    classes.sort_unstable();
+    classes.dedup();
+    if classes.is_empty() {
+        return HashMap::new();
+    }
+
+    // For each class the costs of its words by their part of speech and context ids, along with
+    // the first word, which decides between equally common ones. All classes are collected in a
+    // single pass, as the features of all system words have to be decoded for it
+    type Key = (Vec<String>, i16, i16);
+    let mut found: Vec<HashMap<Key, (usize, Vec<i16>)>> = vec![HashMap::new(); classes.len()];
+    for word in 0..dictionary.len() {
+        let prefix = dictionary.feature_prefix(word, POS_COLUMNS);
+        let pos: Vec<&str> = prefix.split(',').collect();
+        if pos.len() != POS_COLUMNS {
+            continue;
+        }
+
+        for (class, found) in classes.iter().zip(&mut found) {
+            if class.iter().zip(&pos).all(|(level, pos)| level == pos) {
+                let key = (
+                    pos.iter().map(|pos| pos.to_string()).collect(),
+                    dictionary.left_id(word),
+                    dictionary.right_id(word),
+                );
+                let (_, costs) = found.entry(key).or_insert_with(|| (word, Vec::new()));
+                costs.push(dictionary.cost(word));
+            }
+        }
+    }
+
+    classes
+        .into_iter()
+        .zip(found)
+        .filter_map(|(class, found)| {
+            let ((pos, left_id, right_id), (_, mut costs)) = found
+                .into_iter()
+                .max_by_key(|(_, (first, costs))| (costs.len(), std::cmp::Reverse(*first)))?;
+            costs.sort_unstable();
+            let template = Template {
+                pos,
+                left_id,
+                right_id,
+                cost: costs[costs.len() / 2],
+            };
+            Some((class.clone(), template))
+        })
+        .collect()
+}
+
+impl Parser {
+    /// Uses `dictionary` in addition to the system dictionary. Morphemes of the user dictionary
+    /// are returned like any other morpheme. Fails if an entry has context ids the system
+    /// dictionary doesn't define or a word class without system words
+    pub fn with_user_dictionary(
+        mut self,
+        dictionary: UserDictionary,
+    ) -> Result<Self, UserDictionaryError> {
+        let words = UserWords::new(&self.dictionary, dictionary)?;
+        self.user_dictionary = Some(Arc::new(words));
+        Ok(self)
+    }
+
+    /// Returns the user dictionary, if one is used
+    pub fn user_dictionary(&self) -> Option<&UserDictionary> {
+        self.user_dictionary.as_deref().map(|words| &words.source)
+    }
+
+    /// Segments `text` into morphemes of the user and system dictionary. Their `start` is the
+    /// byte offset within `text`
+    pub(crate) fn tag_dictionaries<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+    ) -> Vec<RawMorpheme<'dict, 'text>> {
+        // igo can't add words to its lattice, so user words need the parser's own, as do
+        // dictionaries igo didn't load
+        let tagger = match &self.parser {
+            Some(tagger) if self.user_dictionary.is_none() => tagger,
+            _ => {
+                let user = self.user_dictionary.as_deref();
+                let lattice = Lattice::new(&self.dictionary, user, text);
+                return lattice.morphemes(text, &lattice.best_path().0);
+            }
+        };
+
+        tagger
+            .parse(text)
+            .into_iter()
+            .map(|igo_morph| {
+                let mut raw = RawMorpheme::from(igo_morph);
+                raw.start = byte_offset(text, raw.surface).unwrap_or(raw.start);
+                raw
+            })
+            .collect()
+    }
+}
+
+/// Joins feature columns into a feature string, quoting columns which contain a comma or start
+/// with a quote
+fn join_features<S: AsRef<str>>(columns: &[S]) -> String {
+    let quoted: Vec<_> = columns
+        .iter()
+        .map(|column| {
+            let column = column.as_ref();
+            if column.contains(',') || column.starts_with('"') {
+                format!("\"{}\"", column)
+            } else {
+                column.to_string()
+            }
+        })
+        .collect();
+    quoted.join(",")
+}
+
+/// Splits a CSV line into its columns. Columns may be quoted with `"`, in which case they can
+/// contain commas and `""` for a quote
+fn split_csv(line: &str) -> Vec<String> {
+    let mut columns = Vec::new();
+    let mut column = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                column.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => columns.push(std::mem::take(&mut column)),
+            _ => column.push(c),
+        }
+    }
+
+    columns.push(column);
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::fixture;
+
+    const TOKYO_TO: &str = "東京都,1,1,1000,名詞,固有名詞,地名,一般,*,*,トウキョウト,東京都,東京都,トーキョート,東京都,トーキョート,固,*,*,*,*,*,*,体,トウキョウト,トウキョウト,トウキョウト,トウキョウト,\"1,2\",C1,*,*,*";
+
+    fn dictionary(csv: &str) -> UserDictionary {
+        UserDictionary::from_reader(csv.as_bytes()).unwrap()
+    }
+
+    fn parser(csv: &str) -> Parser {
+        Parser::new(fixture().to_str().unwrap())
+            .unwrap()
+            .with_user_dictionary(dictionary(csv))
+            .unwrap()
+    }
+
+    fn surfaces(parser: &Parser, text: &str) -> Vec<String> {
+        parser
+            .parse(text)
+            .iter()
+            .map(|morph| morph.surface.to_string())
+            .collect()
+    }
+
+    fn words(csv: &str) -> Result<UserWords, UserDictionaryError> {
+        UserWords::new(&Dictionary::load(fixture()).unwrap(), dictionary(csv))
+    }
+
+    #[test]
+    fn entries_compete_with_system_words() {
+        let parser = parser("東京,とうきょう,名詞-固有名詞-地名");
+        assert_eq!(parser.user_dictionary().map(UserDictionary::len), Some(1));
+        assert_eq!(surfaces(&parser, "東京都庁"), vec!["東京都", "庁"]);
+
+        let parser = self::parser("都庁,とちょう,名詞-普通名詞-一般,1000");
+        assert_eq!(surfaces(&parser, "東京都庁"), vec!["東京", "都庁"]);
+        let tocho = &parser.parse("東京都庁")[1];
+        assert_eq!(tocho.reading, "トチョウ");
+        assert_eq!(tocho.word_class, WordClass::Noun(crate::NounType::Common));
+    }
+
+    #[test]
+    fn word_classes_may_be_incomplete() {
+        let words = words("スカイツリー,すかいつりー,名詞").unwrap();
+        let word = word(&words, "スカイツリー");
+        let features = split_features(&word.feature);
+        assert_eq!(features[..4], ["名詞", "普通名詞", "一般", "*"]);
+        assert_eq!((word.left_id, word.right_id, word.cost), (1, 1, 7000));
+
+        let parser = parser("スカイツリー,すかいつりー,名詞,5000");
+        assert_eq!(
+            surfaces(&parser, "東京スカイツリーに"),
+            vec!["東京", "スカイツリー", "に"]
+        );
+        assert_eq!(parser.parse("スカイツリー")[0].reading, "スカイツリー");
+    }
+
+    #[test]
+    fn lex_lines_keep_their_ids_and_cost() {
+        let words = words(TOKYO_TO).unwrap();
+        let word = word(&words, "東京都");
+        assert_eq!((word.left_id, word.right_id, word.cost), (1, 1, 1000));
+
+        let without_ids = TOKYO_TO.replace("1,1,1000", "*,*,*");
+        let words = self::words(&without_ids).unwrap();
+        let word = self::word(&words, "東京都");
+        assert_eq!((word.left_id, word.right_id, word.cost), (1, 1, 3000));
+
+        let err = self::words(&TOKYO_TO.replace("1,1,1000", "1,5000,1000")).unwrap_err();
+        assert!(matches!(
+            err,
+            UserDictionaryError::UnknownContextId {
+                id: 5000,
+                limit: 3,
+                ..
+            }
+        ));
+    }
+
+    fn word<'a>(words: &'a UserWords, surface: &str) -> &'a UserWord {
+        let key: Vec<u16> = surface.encode_utf16().collect();
+        let mut found = None;
+        words.common_prefix(&key, |len, word| {
+            if len == key.len() {
+                found = Some(word);
+            }
+        });
+        words.word(found.unwrap())
+    }
+
+    #[test]
+    fn word_classes_need_system_words() {
+        let err = words("走る,はしる,動詞-一般").unwrap_err();
+        assert!(matches!(
+            err,
+            UserDictionaryError::WordClass { ref word_class, .. } if word_class == "動詞-一般"
+        ));
+    }
+
+    #[test]
+    fn quoted_columns_keep_later_columns_in_place() {
+        let words = words(TOKYO_TO).unwrap();
+        let features = split_features(&word(&words, "東京都").feature);
+        assert_eq!(features.len(), FeatureColumn::ALL.len());
+        assert_eq!(features[FeatureColumn::AType.index()], "1,2");
+        assert_eq!(features[FeatureColumn::AConType.index()], "C1");
+    }
+
+    #[test]
+    fn simplified_values_with_commas_are_quoted() {
+        let mut dictionary = UserDictionary::new();
+        dictionary
+            .add_word("A,B", "えーびー", "名詞-固有名詞", None)
+            .unwrap();
+        let words = UserWords::new(&Dictionary::load(fixture()).unwrap(), dictionary).unwrap();
+
+        let features = split_features(&word(&words, "A,B").feature);
+        assert_eq!(features.len(), FeatureColumn::ALL.len());
+        assert_eq!(features[FeatureColumn::Orth.index()], "A,B");
+        assert_eq!(features[FeatureColumn::Kana.index()], "エービー");
+
+        let mut dictionary = UserDictionary::new();
+        assert!(dictionary.add_word("A\",B", "エー", "名詞", None).is_err());
+        assert!(dictionary
+            .add_word("A", "エー", "名詞--一般", None)
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_lines_are_rejected() {
+        let err = UserDictionary::from_reader("# comment\n\nA,B,C,D,E".as_bytes()).unwrap_err();
+        assert!(matches!(
+            err,
+            UserDictionaryError::Columns { line: 3, count: 5 }
+        ));
+
+        let err = UserDictionary::from_reader("A,エー,名詞,cheap".as_bytes()).unwrap_err();
+        assert!(matches!(err, UserDictionaryError::Cost { line: 1, .. }));
+
+        let err = UserDictionary::from_reader("A,エー,名詞,40000".as_bytes()).unwrap_err();
+        assert!(matches!(err, UserDictionaryError::Cost { line: 1, .. }));
+
+        let err = UserDictionary::from_reader("A,エー,謎".as_bytes()).unwrap_err();
+        assert!(matches!(err, UserDictionaryError::Feature { line: 1, .. }));
+
+        let err =
+            UserDictionary::from_reader(TOKYO_TO.replace("1,1,", "x,1,").as_bytes()).unwrap_err();
+        assert!(
+            matches!(err, UserDictionaryError::ContextId { line: 1, ref value } if value == "x")
+        );
+    }
+
+    #[test]
+    fn csv_columns_may_be_quoted() {
+        assert_eq!(split_csv("a,\"b,c\",\"d\"\"e\""), vec!["a", "b,c", "d\"e"]);
+    }
+}
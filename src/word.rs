@@ -0,0 +1,262 @@
+use std::ops::Range;
+
+use crate::{
+    byte_offset, deinflect, Deinflection, Morpheme, ParseError, Parser, ParticleType, VerbType,
+    WordClass,
+};
+
+/// A content word together with the auxilaries, particles and suffixes conjungating it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Word<'dict, 'input> {
+    /// The whole word as it appears in the input, like 行きました
+    pub surface: &'input str,
+    /// Byte offset of the word within the input
+    pub start: usize,
+    /// The dictionary form of the first morpheme, like 行く
+    pub lemma: &'dict str,
+    /// The morphemes of the word. Never empty
+    pub morphemes: Vec<Morpheme<'dict, 'input>>,
+}
+
+impl<'dict, 'input> Word<'dict, 'input> {
+    /// Returns the morpheme carrying the meaning of the word
+    pub fn head(&self) -> &Morpheme<'dict, 'input> {
+        &self.morphemes[0]
+    }
+
+    /// Returns the byte offset right after the word
+    pub fn end(&self) -> usize {
+        self.start + self.surface.len()
+    }
+
+    /// Returns the byte range of the word within the input
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end()
+    }
+
+    /// Deinflects the word if it is a verb or adjective. See [`deinflect`]
+    pub fn deinflect(&self) -> Option<Deinflection<'dict, 'input>> {
+        deinflect(&self.morphemes)
+    }
+}
+
+impl Parser {
+    /// Parses `text` leniently and groups the morphemes into words. See [`group_words`]
+    pub fn parse_words<'text, 'dict>(&'dict self, text: &'text str) -> Vec<Word<'dict, 'text>> {
+        group_words(text, self.parse_lenient(text))
+    }
+
+    /// Same as [`Parser::parse_words`] but fails like [`Parser::try_parse`] on features which
+    /// can't be mapped
+    pub fn try_parse_words<'text, 'dict>(
+        &'dict self,
+        text: &'text str,
+    ) -> Result<Vec<Word<'dict, 'text>>, ParseError> {
+        Ok(group_words(text, self.try_parse(text)?))
+    }
+}
+
+/// Groups `morphemes`, which have to be parsed from `text`, into words. Auxilaries, the
+/// conjunctive particles て, で and ば, verb and adjective like suffixes and helper verbs like
+/// the いる of ている are attached to the verb or adjective before them. UniDic splits
+/// 行きました into 行き|まし|た, which results in the single word 行きました.
+pub fn group_words<'dict, 'input>(
+    text: &'input str,
+    morphemes: Vec<Morpheme<'dict, 'input>>,
+) -> Vec<Word<'dict, 'input>> {
+    let mut words: Vec<Word> = Vec::new();
+
+    for morph in morphemes {
+        let start = byte_offset(text, morph.surface).unwrap_or(morph.start);
+
+        if let Some(word) = words.last_mut() {
+            let contiguous = word.end() == start;
+            if contiguous && is_inflectable(word.head()) && attaches(word, &morph) {
+                word.surface = &text[word.start..start + morph.surface.len()];
+                word.morphemes.push(morph);
+                continue;
+            }
+        }
+
+        words.push(Word {
+            surface: morph.surface,
+            start,
+            lemma: morph.lexeme,
+            morphemes: vec![morph],
+        });
+    }
+
+    words
+}
+
+/// Returns `true` if other morphemes can be attached to a word starting with `head`
+fn is_inflectable(head: &Morpheme) -> bool {
+    match head.word_class {
+        WordClass::Verb(VerbType::Auxilary(_)) => false,
+        WordClass::Verb(_) | WordClass::Adjective(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns `true` if `morph` belongs to `word`
+fn attaches(word: &Word, morph: &Morpheme) -> bool {
+    let pos2 = morph.features().pos2;
+    let after_te = word
+        .morphemes
+        .last()
+        .is_some_and(|last| is_conjunctive(last) && last.surface != "ば");
+
+    match morph.word_class {
+        WordClass::Verb(VerbType::Auxilary(_)) => true,
+        WordClass::Particle(ParticleType::Conjungtion) => is_conjunctive(morph),
+        WordClass::Suffix => pos2 == "動詞的" || pos2 == "形容詞的",
+        // ている, てしまう
+        WordClass::Verb(_) => after_te && pos2 == "非自立可能",
+        // 高くない
+        WordClass::Adjective(_) => pos2 == "非自立可能",
+        _ => false,
+    }
+}
+
+/// Returns `true` for the conjunctive particles attaching to a verb
+fn is_conjunctive(morph: &Morpheme) -> bool {
+    morph.word_class == WordClass::Particle(ParticleType::Conjungtion)
+        && matches!(morph.surface, "て" | "で" | "ば")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::morph;
+
+    /// Groups morphemes given as surface and the part of speech, cType and cForm columns,
+    /// returning the surfaces of the words
+    fn grouped(morphemes: &[(&str, &str)]) -> Vec<String> {
+        let text: String = morphemes.iter().map(|(surface, _)| *surface).collect();
+        let features: Vec<String> = morphemes
+            .iter()
+            .map(|(surface, columns)| format!("{},*,{},*,{},*,和", columns, surface, surface))
+            .collect();
+
+        let mut start = 0;
+        let morphemes = morphemes
+            .iter()
+            .zip(&features)
+            .map(|((surface, _), feature)| {
+                let morph = morph(&text[start..start + surface.len()], start, feature);
+                start += surface.len();
+                morph
+            })
+            .collect();
+
+        group_words(&text, morphemes)
+            .iter()
+            .map(|word| word.surface.to_string())
+            .collect()
+    }
+
+    const VERB: &str = "動詞,一般,*,*,下一段-バ行,連用形-一般";
+    const TE: &str = "助詞,接続助詞,*,*,*,*";
+
+    #[test]
+    fn auxilary_chain() {
+        let words = grouped(&[
+            ("食べ", VERB),
+            ("させ", "助動詞,*,*,*,下一段-サ行,未然形-一般"),
+            ("られ", "助動詞,*,*,*,助動詞-レル,未然形-一般"),
+            ("なかっ", "助動詞,*,*,*,助動詞-ナイ,連用形-促音便"),
+            ("た", "助動詞,*,*,*,助動詞-タ,終止形-一般"),
+            ("。", "補助記号,句点,*,*,*,*"),
+        ]);
+        assert_eq!(words, vec!["食べさせられなかった", "。"]);
+    }
+
+    #[test]
+    fn conjunctive_particles() {
+        assert_eq!(grouped(&[("食べ", VERB), ("て", TE)]), vec!["食べて"]);
+        assert_eq!(
+            grouped(&[
+                ("読ん", "動詞,一般,*,*,五段-マ行,連用形-撥音便"),
+                ("で", TE)
+            ]),
+            vec!["読んで"]
+        );
+        assert_eq!(
+            grouped(&[
+                ("行け", "動詞,非自立可能,*,*,五段-カ行,仮定形-一般"),
+                ("ば", TE)
+            ]),
+            vec!["行けば"]
+        );
+        // Other conjunctive particles start a word of their own
+        assert_eq!(
+            grouped(&[("食べる", VERB), ("から", TE)]),
+            vec!["食べる", "から"]
+        );
+    }
+
+    #[test]
+    fn suffixes() {
+        let verb_like = grouped(&[
+            ("寒", "形容詞,一般,*,*,形容詞,語幹-一般"),
+            ("がっ", "接尾辞,動詞的,*,*,五段-ラ行,連用形-促音便"),
+            ("た", "助動詞,*,*,*,助動詞-タ,終止形-一般"),
+        ]);
+        assert_eq!(verb_like, vec!["寒がった"]);
+
+        let adjective_like = grouped(&[
+            ("食べ", VERB),
+            ("づらい", "接尾辞,形容詞的,*,*,形容詞,終止形-一般"),
+        ]);
+        assert_eq!(adjective_like, vec!["食べづらい"]);
+
+        // Noun like suffixes don't conjungate the verb
+        let noun_like = grouped(&[("食べ", VERB), ("方", "接尾辞,名詞的,一般,*,*,*")]);
+        assert_eq!(noun_like, vec!["食べ", "方"]);
+    }
+
+    #[test]
+    fn helper_verbs() {
+        let progressive = grouped(&[
+            ("食べ", VERB),
+            ("て", TE),
+            ("いる", "動詞,非自立可能,*,*,上一段-ア行,終止形-一般"),
+        ]);
+        assert_eq!(progressive, vec!["食べている"]);
+
+        // Without て, the helper verb is a word of its own
+        let separate = grouped(&[
+            ("食べる", VERB),
+            ("いる", "動詞,非自立可能,*,*,上一段-ア行,終止形-一般"),
+        ]);
+        assert_eq!(separate, vec!["食べる", "いる"]);
+
+        let standalone = grouped(&[
+            ("本", "名詞,普通名詞,一般,*,*,*"),
+            ("が", "助詞,格助詞,*,*,*,*"),
+            ("ある", "動詞,非自立可能,*,*,五段-ラ行,終止形-一般"),
+        ]);
+        assert_eq!(standalone, vec!["本", "が", "ある"]);
+    }
+
+    #[test]
+    fn adjectives() {
+        let negative = grouped(&[
+            ("高く", "形容詞,一般,*,*,形容詞,連用形-一般"),
+            ("ない", "形容詞,非自立可能,*,*,形容詞,終止形-一般"),
+        ]);
+        assert_eq!(negative, vec!["高くない"]);
+
+        let polite = grouped(&[
+            ("高い", "形容詞,一般,*,*,形容詞,終止形-一般"),
+            ("です", "助動詞,*,*,*,助動詞-デス,終止形-一般"),
+        ]);
+        assert_eq!(polite, vec!["高いです"]);
+
+        let separate = grouped(&[
+            ("高い", "形容詞,一般,*,*,形容詞,連体形-一般"),
+            ("山", "名詞,普通名詞,一般,*,*,*"),
+        ]);
+        assert_eq!(separate, vec!["高い", "山"]);
+    }
+}